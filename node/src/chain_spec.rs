@@ -1,6 +1,6 @@
 use node_template_runtime::{
-	AccountId, AssetsConfig, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature,
-	SudoConfig, SystemConfig, WASM_BINARY,
+	AccountId, AssetsConfig, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
+	NFTMakerConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -160,6 +160,9 @@ fn testnet_genesis(
 	accounts.append(&mut fort_wow_lp_token);
 	accounts.append(&mut lp_pow_lp_fow);
 
+	// Demo NFT metadata, see the nft_maker `TokenUri` for the supported locations
+	let heroes_uri = "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
 	GenesisConfig {
 		system: SystemConfig {
 			// Add Wasm runtime to storage.
@@ -201,5 +204,13 @@ fn testnet_genesis(
 				accounts,
 			}
 		},
+		nft_maker: NFTMakerConfig {
+			collections: vec![(0, alice.clone(), heroes_uri.as_bytes().to_vec(), Some(1_000))],
+			items: (0..3)
+				.map(|id| {
+					(0, id, alice.clone(), format!("{}/{}.json", heroes_uri, id).into_bytes())
+				})
+				.collect(),
+		},
 	}
 }
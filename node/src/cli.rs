@@ -7,6 +7,11 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Price feed the oracle's offchain worker queries, e.g. `http://localhost:8000/prices`.
+	/// Stored in the node's offchain local storage, so it's kept across restarts.
+	#[clap(long)]
+	pub price_feed_url: Option<String>,
 }
 
 #[derive(Debug, clap::Subcommand)]
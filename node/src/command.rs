@@ -169,8 +169,9 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let price_feed_url = cli.price_feed_url.clone();
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(config, price_feed_url).map_err(sc_cli::Error::Service)
 			})
 		},
 	}
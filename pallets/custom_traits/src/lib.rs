@@ -1,9 +1,73 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub trait Ownership<Id, AccountId> {
-	fn is_owner(id: &Id, who: &AccountId) -> bool;
+use frame_support::{dispatch::DispatchResult, traits::LockIdentifier};
+use sp_runtime::{DispatchError, Permill};
+use sp_std::vec::Vec;
+
+pub trait Ownership<CollectionId, Id, AccountId> {
+	fn is_owner(collection_id: &CollectionId, id: &Id, who: &AccountId) -> bool;
+}
+
+pub trait Transfer<CollectionId, Id, AccountId> {
+	fn transfer(collection_id: &CollectionId, id: &Id, to: &AccountId) -> bool;
+
+	/// Whether the item can currently be transferred by its owner
+	fn can_transfer(collection_id: &CollectionId, id: &Id) -> bool;
+}
+
+/// Mints items with the next free ID, e.g. the outputs of crafting
+pub trait Mint<CollectionId, Id, AccountId> {
+	/// Mint an item into a collection for `owner`, returning its ID
+	fn mint(collection_id: &CollectionId, owner: &AccountId) -> Result<Id, DispatchError>;
+
+	/// Whether a collection reached its max supply, so nothing can be minted into it
+	fn is_full(collection_id: &CollectionId) -> bool;
+}
+
+/// Locks that keep an item from being transferred or burned, e.g. while it is equipped or staked
+pub trait Lockable<CollectionId, Id> {
+	/// Take the lock `lock_id` on an item
+	fn lock(collection_id: &CollectionId, id: &Id, lock_id: LockIdentifier) -> DispatchResult;
+
+	/// Release the lock `lock_id`. The item stays locked while other locks remain.
+	fn unlock(collection_id: &CollectionId, id: &Id, lock_id: LockIdentifier) -> DispatchResult;
+
+	fn is_locked(collection_id: &CollectionId, id: &Id) -> bool;
 }
 
-pub trait Transfer<Id, AccountId> {
-	fn transfer(id: &Id, to: &AccountId) -> bool;
+/// Items whose use can be granted to an account other than their owner for a while, e.g. rentals
+pub trait Rentable<CollectionId, Id, AccountId, BlockNumber> {
+	/// The account currently using an item. Expired users are ignored.
+	fn user(collection_id: &CollectionId, id: &Id) -> Option<AccountId>;
+
+	/// Let `user` use an item until block `expires_at`. Fails while another user's use is active.
+	fn set_user(
+		collection_id: &CollectionId,
+		id: &Id,
+		user: &AccountId,
+		expires_at: BlockNumber,
+	) -> DispatchResult;
+}
+
+pub trait Royalty<CollectionId, Id, AccountId> {
+	/// The account owed a royalty on sales of an item, and its share of the price
+	fn royalty(collection_id: &CollectionId, id: &Id) -> Option<(AccountId, Permill)>;
+}
+
+/// Called after an item is burned, e.g. to remove listings that reference it
+pub trait OnBurn<CollectionId, Id> {
+	fn on_burn(collection_id: &CollectionId, id: &Id);
+}
+
+impl<CollectionId, Id> OnBurn<CollectionId, Id> for () {
+	fn on_burn(_collection_id: &CollectionId, _id: &Id) {}
+}
+
+pub trait PriceProvider<AssetId, Balance> {
+	/// The latest price of an asset as (price, quote currency, decimals), or `None` when it has
+	/// no price or the price is stale
+	fn price(asset_id: &AssetId) -> Option<(Balance, Vec<u8>, u8)>;
+
+	/// The assets prices are provided for
+	fn priced_assets() -> Vec<AssetId>;
 }
@@ -0,0 +1,251 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod types;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		pallet_prelude::*,
+		traits::tokens::{
+			fungibles::{Inspect, Mutate},
+			nonfungibles::{Inspect as NftInspect, Mutate as NftMutate},
+		},
+	};
+	use frame_system::pallet_prelude::*;
+	use pallet_custom_traits::Mint;
+	use sp_runtime::traits::{AtLeast32BitUnsigned, CheckedAdd, One, Zero};
+	use sp_std::vec::Vec;
+
+	use crate::types::Recipe;
+	pub use crate::types::{NftInput, NftOutput};
+
+	type AssetIdOf<T: Config> = <T::Assets as Inspect<T::AccountId>>::AssetId;
+	type BalanceOf<T: Config> = <T::Assets as Inspect<T::AccountId>>::Balance;
+	pub type AttributeOf<T> = (
+		BoundedVec<u8, <T as Config>::AttributeKeyLimit>,
+		BoundedVec<u8, <T as Config>::AttributeValueLimit>,
+	);
+	pub type NftInputOf<T> = NftInput<<T as Config>::CollectionId, AttributeOf<T>>;
+	pub type NftOutputOf<T> = NftOutput<
+		<T as Config>::CollectionId,
+		BoundedVec<AttributeOf<T>, <T as Config>::MaxOutputAttributes>,
+	>;
+	type RecipeOf<T> = Recipe<
+		<T as frame_system::Config>::AccountId,
+		BoundedVec<NftInputOf<T>, <T as Config>::MaxInputs>,
+		BoundedVec<(AssetIdOf<T>, BalanceOf<T>), <T as Config>::MaxInputs>,
+		BoundedVec<NftOutputOf<T>, <T as Config>::MaxOutputs>,
+	>;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		type Assets: Inspect<Self::AccountId> + Mutate<Self::AccountId>;
+		type CollectionId: Member + Parameter + MaxEncodedLen + Copy;
+		type ItemId: Member + Parameter + MaxEncodedLen + Copy;
+		type RecipeId: Member + Parameter + MaxEncodedLen + Copy + AtLeast32BitUnsigned + Default;
+		type Nfts: NftInspect<Self::AccountId, CollectionId = Self::CollectionId, ItemId = Self::ItemId>
+			+ NftMutate<Self::AccountId>
+			+ Mint<Self::CollectionId, Self::ItemId, Self::AccountId>;
+
+		/// Max length of the attribute keys of inputs and outputs
+		#[pallet::constant]
+		type AttributeKeyLimit: Get<u32>;
+
+		/// Max length of the attribute values of inputs and outputs
+		#[pallet::constant]
+		type AttributeValueLimit: Get<u32>;
+
+		/// Most NFT inputs, and most asset inputs, of a recipe
+		#[pallet::constant]
+		type MaxInputs: Get<u32>;
+
+		/// Most items a recipe mints
+		#[pallet::constant]
+		type MaxOutputs: Get<u32>;
+
+		/// Most attributes set on each output
+		#[pallet::constant]
+		type MaxOutputAttributes: Get<u32>;
+	}
+
+	#[pallet::storage]
+	pub(super) type Recipes<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::RecipeId, RecipeOf<T>, OptionQuery>;
+
+	#[pallet::storage]
+	pub(super) type NextRecipeId<T: Config> = StorageValue<_, T::RecipeId, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		// (recipe ID, owner)
+		RecipeCreated(T::RecipeId, T::AccountId),
+		// (recipe ID)
+		RecipeRemoved(T::RecipeId),
+		// (recipe ID, crafter, minted (collection ID, item ID)s)
+		Crafted(T::RecipeId, T::AccountId, Vec<(T::CollectionId, T::ItemId)>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		NoInputs,
+		NoOutputs,
+		NotCollectionOwner,
+		RecipeNotFound,
+		NotRecipeOwner,
+		WrongInputCount,
+		DuplicateInput,
+		NotItemOwner,
+		InputMismatch,
+		Overflow,
+		ZeroAmount,
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	impl<T: Config> Pallet<T> {
+		/// Whether an item fits an input of a recipe
+		fn matches(
+			input: &NftInputOf<T>,
+			collection_id: &T::CollectionId,
+			item_id: &T::ItemId,
+		) -> bool {
+			if input.collection_id != *collection_id {
+				return false
+			}
+
+			match &input.attribute {
+				Some((key, value)) =>
+					T::Nfts::attribute(collection_id, item_id, key).as_deref() == Some(&value[..]),
+				None => true,
+			}
+		}
+
+		/// Weight of `craft` with `nft_inputs` items, assuming the recipe has the most asset
+		/// inputs and outputs, and every output the most attributes
+		pub(crate) fn craft_weight(nft_inputs: u32) -> Weight {
+			let nft_inputs = u64::from(nft_inputs);
+			let asset_inputs = u64::from(T::MaxInputs::get());
+			let outputs = u64::from(T::MaxOutputs::get());
+			let attributes = outputs * u64::from(T::MaxOutputAttributes::get());
+			// Checking and burning an item, burning an asset, minting an item, setting an
+			// attribute
+			let reads = 1 + nft_inputs * 8 + asset_inputs * 2 + outputs * 4 + attributes * 3;
+			let writes = nft_inputs * 8 + asset_inputs * 2 + outputs * 6 + attributes * 4;
+			let steps = nft_inputs + asset_inputs + outputs + attributes;
+			T::DbWeight::get()
+				.reads_writes(reads, writes)
+				.saturating_add(5_000_000)
+				.saturating_add(steps.saturating_mul(1_000_000))
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a recipe that mints `outputs` from `nft_inputs` and `asset_inputs`. The
+		/// caller must own the collections of the outputs, and asset inputs can't be zero.
+		#[pallet::weight(5_000_000)]
+		pub fn create_recipe(
+			origin: OriginFor<T>,
+			nft_inputs: BoundedVec<NftInputOf<T>, T::MaxInputs>,
+			asset_inputs: BoundedVec<(AssetIdOf<T>, BalanceOf<T>), T::MaxInputs>,
+			outputs: BoundedVec<NftOutputOf<T>, T::MaxOutputs>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!nft_inputs.is_empty() || !asset_inputs.is_empty(), Error::<T>::NoInputs);
+			ensure!(!outputs.is_empty(), Error::<T>::NoOutputs);
+			ensure!(
+				asset_inputs.iter().all(|(_, amount)| !amount.is_zero()),
+				Error::<T>::ZeroAmount,
+			);
+			for output in outputs.iter() {
+				ensure!(
+					T::Nfts::collection_owner(&output.collection_id).as_ref() == Some(&sender),
+					Error::<T>::NotCollectionOwner,
+				);
+			}
+
+			let recipe_id = NextRecipeId::<T>::get();
+			let next = recipe_id.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
+			NextRecipeId::<T>::put(next);
+
+			let recipe = Recipe { owner: sender.clone(), nft_inputs, asset_inputs, outputs };
+			Recipes::<T>::insert(recipe_id, recipe);
+
+			Self::deposit_event(Event::<T>::RecipeCreated(recipe_id, sender));
+
+			Ok(())
+		}
+
+		#[pallet::weight(1_000_000)]
+		pub fn remove_recipe(origin: OriginFor<T>, recipe_id: T::RecipeId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let recipe = Recipes::<T>::get(recipe_id).ok_or(Error::<T>::RecipeNotFound)?;
+			ensure!(recipe.owner == sender, Error::<T>::NotRecipeOwner);
+
+			Recipes::<T>::remove(recipe_id);
+
+			Self::deposit_event(Event::<T>::RecipeRemoved(recipe_id));
+
+			Ok(())
+		}
+
+		/// Craft a recipe with `items`, one of the caller's items for each NFT input in order.
+		/// Consumed items and the asset inputs are burned, and the outputs minted to the caller,
+		/// all or nothing.
+		#[pallet::weight(Pallet::<T>::craft_weight(items.len() as u32))]
+		pub fn craft(
+			origin: OriginFor<T>,
+			recipe_id: T::RecipeId,
+			items: BoundedVec<(T::CollectionId, T::ItemId), T::MaxInputs>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let recipe = Recipes::<T>::get(recipe_id).ok_or(Error::<T>::RecipeNotFound)?;
+			ensure!(items.len() == recipe.nft_inputs.len(), Error::<T>::WrongInputCount);
+
+			for (index, (input, (collection_id, item_id))) in
+				recipe.nft_inputs.iter().zip(items.iter()).enumerate()
+			{
+				ensure!(
+					!items[..index].contains(&(*collection_id, *item_id)),
+					Error::<T>::DuplicateInput,
+				);
+				ensure!(
+					T::Nfts::owner(collection_id, item_id).as_ref() == Some(&sender),
+					Error::<T>::NotItemOwner,
+				);
+				ensure!(Self::matches(input, collection_id, item_id), Error::<T>::InputMismatch);
+			}
+
+			for (input, (collection_id, item_id)) in recipe.nft_inputs.iter().zip(items.iter()) {
+				if input.consume {
+					T::Nfts::burn(collection_id, item_id, Some(&sender))?;
+				}
+			}
+			for (asset_id, amount) in recipe.asset_inputs {
+				T::Assets::burn_from(asset_id, &sender, amount)?;
+			}
+
+			let mut minted = Vec::new();
+			for output in recipe.outputs {
+				let item_id = T::Nfts::mint(&output.collection_id, &sender)?;
+				for (key, value) in output.attributes {
+					T::Nfts::set_attribute(&output.collection_id, &item_id, &key, &value)?;
+				}
+				minted.push((output.collection_id, item_id));
+			}
+
+			Self::deposit_event(Event::<T>::Crafted(recipe_id, sender, minted));
+
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,20 @@
+use crate as pallet_nft_crafting;
+use frame_support::traits::ConstU32;
+
+pallet_nft_maker::nft_maker_mock! {
+	Crafting: pallet_nft_crafting::{Pallet, Call, Storage, Event<T>},
+}
+
+impl pallet_nft_crafting::Config for Test {
+	type Event = Event;
+	type Assets = Assets;
+	type CollectionId = u32;
+	type ItemId = u32;
+	type RecipeId = u32;
+	type Nfts = NftMaker;
+	type AttributeKeyLimit = ConstU32<16>;
+	type AttributeValueLimit = ConstU32<32>;
+	type MaxInputs = ConstU32<3>;
+	type MaxOutputs = ConstU32<2>;
+	type MaxOutputAttributes = ConstU32<2>;
+}
@@ -0,0 +1,183 @@
+use crate::{mock::*, Error, NftInput, NftInputOf, NftOutput, NftOutputOf};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{tokens::nonfungibles::Inspect as NftInspect, ConstU32},
+	BoundedVec,
+};
+use pallet_custom_traits::Lockable;
+
+const GOLD: u32 = 0;
+
+struct World {
+	ores: u32,
+	tools: u32,
+	swords: u32,
+}
+
+/// ALICE owns the collections, BOB holds gold to craft with
+fn setup() -> World {
+	assert_ok!(Assets::force_create(Origin::root(), GOLD, ALICE, true, 1));
+	assert_ok!(Assets::mint(Origin::signed(ALICE), GOLD, BOB, 100));
+	World {
+		ores: create_collection(ALICE),
+		tools: create_collection(ALICE),
+		swords: create_collection(ALICE),
+	}
+}
+
+/// Mint an item of a collection ALICE owns to BOB, with an optional `kind` attribute
+fn give(collection_id: u32, kind: Option<&[u8]>) -> u32 {
+	let item_id = mint(ALICE, collection_id);
+	if let Some(kind) = kind {
+		assert_ok!(NftMaker::set_attribute(
+			Origin::signed(ALICE),
+			collection_id,
+			item_id,
+			b"kind".to_vec().try_into().unwrap(),
+			kind.to_vec().try_into().unwrap()
+		));
+	}
+	assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, item_id, BOB));
+	item_id
+}
+
+fn input(collection_id: u32, kind: Option<&[u8]>, consume: bool) -> NftInputOf<Test> {
+	let attribute =
+		kind.map(|kind| (b"kind".to_vec().try_into().unwrap(), kind.to_vec().try_into().unwrap()));
+	NftInput { collection_id, attribute, consume }
+}
+
+fn output(collection_id: u32, kind: &[u8]) -> NftOutputOf<Test> {
+	let attribute = (b"kind".to_vec().try_into().unwrap(), kind.to_vec().try_into().unwrap());
+	NftOutput { collection_id, attributes: vec![attribute].try_into().unwrap() }
+}
+
+/// Recipe 0: an ore, a tool that is kept and 50 gold make a sword
+fn create_sword_recipe(world: &World) {
+	assert_ok!(Crafting::create_recipe(
+		Origin::signed(ALICE),
+		vec![input(world.ores, Some(b"iron"), true), input(world.tools, None, false)]
+			.try_into()
+			.unwrap(),
+		vec![(GOLD, 50)].try_into().unwrap(),
+		vec![output(world.swords, b"sword")].try_into().unwrap()
+	));
+}
+
+fn items(items: &[(u32, u32)]) -> BoundedVec<(u32, u32), ConstU32<3>> {
+	items.to_vec().try_into().unwrap()
+}
+
+#[test]
+fn craft_consumes_inputs_and_mints_outputs() {
+	new_test_ext().execute_with(|| {
+		let world = setup();
+		create_sword_recipe(&world);
+		let ore = give(world.ores, Some(b"iron"));
+		let hammer = give(world.tools, None);
+
+		assert_ok!(Crafting::craft(
+			Origin::signed(BOB),
+			0,
+			items(&[(world.ores, ore), (world.tools, hammer)])
+		));
+
+		assert_eq!(NftMaker::owner(&world.ores, &ore), None);
+		assert_eq!(NftMaker::owner(&world.tools, &hammer), Some(BOB));
+		assert_eq!(Assets::balance(GOLD, BOB), 50);
+		let sword = match last_event() {
+			Event::Crafting(crate::Event::Crafted(0, BOB, minted)) => minted[0].1,
+			event => panic!("Unexpected event {:?}", event),
+		};
+		assert_eq!(NftMaker::owner(&world.swords, &sword), Some(BOB));
+		assert_eq!(NftMaker::attribute(&world.swords, &sword, b"kind"), Some(b"sword".to_vec()));
+	});
+}
+
+#[test]
+fn craft_requires_owned_matching_inputs() {
+	new_test_ext().execute_with(|| {
+		let world = setup();
+		create_sword_recipe(&world);
+		let copper = give(world.ores, Some(b"copper"));
+		let hammer = give(world.tools, None);
+		let alices_ore = mint(ALICE, world.ores);
+
+		assert_noop!(
+			Crafting::craft(
+				Origin::signed(BOB),
+				0,
+				items(&[(world.ores, copper), (world.tools, hammer)])
+			),
+			Error::<Test>::InputMismatch
+		);
+		assert_noop!(
+			Crafting::craft(
+				Origin::signed(BOB),
+				0,
+				items(&[(world.ores, alices_ore), (world.tools, hammer)])
+			),
+			Error::<Test>::NotItemOwner
+		);
+		assert_noop!(
+			Crafting::craft(Origin::signed(BOB), 0, items(&[(world.tools, hammer)])),
+			Error::<Test>::WrongInputCount
+		);
+	});
+}
+
+#[test]
+fn craft_reverts_everything_if_an_input_is_locked() {
+	new_test_ext().execute_with(|| {
+		let world = setup();
+		assert_ok!(Crafting::create_recipe(
+			Origin::signed(ALICE),
+			vec![input(world.ores, None, true), input(world.ores, None, true)]
+				.try_into()
+				.unwrap(),
+			vec![(GOLD, 50)].try_into().unwrap(),
+			vec![output(world.swords, b"sword")].try_into().unwrap()
+		));
+		let first = give(world.ores, None);
+		let second = give(world.ores, None);
+		assert_ok!(NftMaker::lock(&world.ores, &second, *b"stakings"));
+
+		assert_noop!(
+			Crafting::craft(
+				Origin::signed(BOB),
+				0,
+				items(&[(world.ores, first), (world.ores, second)])
+			),
+			pallet_nft_maker::Error::<Test>::Locked
+		);
+		assert_eq!(NftMaker::owner(&world.ores, &first), Some(BOB));
+		assert_eq!(Assets::balance(GOLD, BOB), 100);
+	});
+}
+
+#[test]
+fn create_recipe_checks_its_inputs_and_outputs() {
+	new_test_ext().execute_with(|| {
+		let world = setup();
+		let bobs_collection = create_collection(BOB);
+
+		assert_noop!(
+			Crafting::create_recipe(
+				Origin::signed(ALICE),
+				vec![input(world.ores, None, true)].try_into().unwrap(),
+				vec![(GOLD, 0)].try_into().unwrap(),
+				vec![output(world.swords, b"sword")].try_into().unwrap()
+			),
+			Error::<Test>::ZeroAmount
+		);
+		assert_noop!(
+			Crafting::create_recipe(
+				Origin::signed(ALICE),
+				vec![input(world.ores, None, true)].try_into().unwrap(),
+				Default::default(),
+				vec![output(bobs_collection, b"sword")].try_into().unwrap()
+			),
+			Error::<Test>::NotCollectionOwner
+		);
+	});
+}
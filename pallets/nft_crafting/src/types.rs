@@ -0,0 +1,31 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::RuntimeDebug;
+use scale_info::TypeInfo;
+
+/// A NFT a recipe takes
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct NftInput<CollectionId, Attribute> {
+	pub collection_id: CollectionId,
+	/// (key, value) of an attribute the item must have
+	pub attribute: Option<Attribute>,
+	/// Consumed items are burned. Others only have to be owned, e.g. a hammer.
+	pub consume: bool,
+}
+
+/// An item a recipe mints
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct NftOutput<CollectionId, Attributes> {
+	pub collection_id: CollectionId,
+	/// (key, value) attributes set on the minted item
+	pub attributes: Attributes,
+}
+
+#[derive(Clone, Encode, Decode, Eq, PartialEq, TypeInfo, MaxEncodedLen)]
+pub struct Recipe<AccountId, NftInputs, AssetInputs, Outputs> {
+	/// Owner of the output collections
+	pub(super) owner: AccountId,
+	pub(super) nft_inputs: NftInputs,
+	/// (asset ID, amount) burned from the crafter
+	pub(super) asset_inputs: AssetInputs,
+	pub(super) outputs: Outputs,
+}
@@ -0,0 +1,207 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod types;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		pallet_prelude::*,
+		traits::tokens::{
+			fungibles::{metadata::Mutate as MetadataMutate, Create, Inspect, Mutate},
+			nonfungibles::{Inspect as NftInspect, Transfer as NftTransfer},
+		},
+		traits::{Currency, LockIdentifier, ReservableCurrency},
+		PalletId,
+	};
+	use frame_system::pallet_prelude::*;
+	use pallet_custom_traits::Lockable;
+	use sp_runtime::traits::{AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, One, Zero};
+
+	use crate::types::Vault;
+
+	type BalanceOf<T: Config> = <T::Assets as Inspect<T::AccountId>>::Balance;
+	type DepositBalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		type AssetId: Member + Parameter + MaxEncodedLen + Copy + AtLeast32BitUnsigned;
+		type Assets: Inspect<Self::AccountId, AssetId = Self::AssetId>
+			+ Create<Self::AccountId>
+			+ Mutate<Self::AccountId>
+			+ MetadataMutate<Self::AccountId>;
+		type CollectionId: Member + Parameter + MaxEncodedLen + Copy;
+		type ItemId: Member + Parameter + MaxEncodedLen + Copy;
+		type Nfts: NftInspect<Self::AccountId, CollectionId = Self::CollectionId, ItemId = Self::ItemId>
+			+ NftTransfer<Self::AccountId>
+			+ Lockable<Self::CollectionId, Self::ItemId>;
+
+		/// Holds the NFTs in custody, and administers their share assets. Metadata deposits of
+		/// the assets pallet, if any, are reserved from this account.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// First ID given to share assets. IDs from it on should be left to this pallet, IDs
+		/// already taken by other assets are skipped.
+		#[pallet::constant]
+		type FirstShareAssetId: Get<Self::AssetId>;
+
+		/// Name of share assets
+		#[pallet::constant]
+		type ShareName: Get<&'static str>;
+
+		/// Symbol of share assets
+		#[pallet::constant]
+		type ShareSymbol: Get<&'static str>;
+
+		/// Currency vault deposits are reserved in
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Deposit reserved from the owner of a NFT while it's fractionalized
+		#[pallet::constant]
+		type VaultDeposit: Get<DepositBalanceOf<Self>>;
+	}
+
+	/// Fractionalized NFTs by the ID of their share asset
+	#[pallet::storage]
+	pub(super) type Vaults<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		Vault<T::AccountId, T::CollectionId, T::ItemId, BalanceOf<T>, DepositBalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	/// ID the next share asset gets, unless it's taken. `FirstShareAssetId` if unset.
+	#[pallet::storage]
+	pub(super) type NextShareAssetId<T: Config> = StorageValue<_, T::AssetId, OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		// (collection ID, item ID, share asset ID, shares, owner)
+		Fractionalized(T::CollectionId, T::ItemId, T::AssetId, BalanceOf<T>, T::AccountId),
+		// (collection ID, item ID, share asset ID, redeemer)
+		Redeemed(T::CollectionId, T::ItemId, T::AssetId, T::AccountId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		NotItemOwner,
+		InvalidShares,
+		VaultNotFound,
+		InsufficientShares,
+		Overflow,
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// Keeps NFTs in custody from being burned
+	const FRACTIONS_LOCK: LockIdentifier = *b"fraction";
+
+	impl<T: Config> Pallet<T> {
+		pub(crate) fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// Take the next share asset ID that no asset has. Assets always have a non-zero minimum
+		/// balance, so a zero one means the ID is free.
+		fn next_share_asset_id() -> Result<T::AssetId, DispatchError> {
+			let mut asset_id =
+				NextShareAssetId::<T>::get().unwrap_or_else(T::FirstShareAssetId::get);
+			while !T::Assets::minimum_balance(asset_id).is_zero() {
+				asset_id = asset_id.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
+			}
+			let next = asset_id.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
+			NextShareAssetId::<T>::put(next);
+			Ok(asset_id)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Lock a NFT in custody and mint `shares` of it to the caller as a new asset, reserving
+		/// `VaultDeposit` until it's redeemed. The shares can be traded like any other asset, e.g.
+		/// in Dex pools.
+		#[pallet::weight(5_000_000)]
+		pub fn fractionalize(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			shares: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!shares.is_zero(), Error::<T>::InvalidShares);
+			ensure!(
+				T::Nfts::owner(&collection_id, &item_id).as_ref() == Some(&sender),
+				Error::<T>::NotItemOwner,
+			);
+
+			let deposit = T::VaultDeposit::get();
+			T::Currency::reserve(&sender, deposit)?;
+
+			let custody = Self::account_id();
+			let asset_id = Self::next_share_asset_id()?;
+			T::Assets::create(asset_id, custody.clone(), false, One::one())?;
+			T::Assets::set(
+				asset_id,
+				&custody,
+				T::ShareName::get().as_bytes().to_vec(),
+				T::ShareSymbol::get().as_bytes().to_vec(),
+				0,
+			)?;
+			T::Assets::mint_into(asset_id, &sender, shares)?;
+			T::Nfts::transfer(&collection_id, &item_id, &custody)?;
+			T::Nfts::lock(&collection_id, &item_id, FRACTIONS_LOCK)?;
+
+			let vault = Vault { owner: sender.clone(), collection_id, item_id, shares, deposit };
+			Vaults::<T>::insert(asset_id, vault);
+
+			Self::deposit_event(Event::<T>::Fractionalized(
+				collection_id,
+				item_id,
+				asset_id,
+				shares,
+				sender,
+			));
+
+			Ok(())
+		}
+
+		/// Burn every share of `asset_id` and take the NFT out of custody. The caller must hold
+		/// all of the shares. The deposit is returned to whoever fractionalized the NFT.
+		#[pallet::weight(5_000_000)]
+		pub fn redeem(origin: OriginFor<T>, asset_id: T::AssetId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let vault = Vaults::<T>::get(asset_id).ok_or(Error::<T>::VaultNotFound)?;
+
+			ensure!(
+				T::Assets::balance(asset_id, &sender) >= vault.shares,
+				Error::<T>::InsufficientShares,
+			);
+
+			T::Assets::burn_from(asset_id, &sender, vault.shares)?;
+			T::Nfts::unlock(&vault.collection_id, &vault.item_id, FRACTIONS_LOCK)?;
+			T::Nfts::transfer(&vault.collection_id, &vault.item_id, &sender)?;
+			T::Currency::unreserve(&vault.owner, vault.deposit);
+			Vaults::<T>::remove(asset_id);
+
+			Self::deposit_event(Event::<T>::Redeemed(
+				vault.collection_id,
+				vault.item_id,
+				asset_id,
+				sender,
+			));
+
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,37 @@
+use crate as pallet_nft_fractions;
+use frame_support::{
+	parameter_types,
+	traits::{ConstU32, ConstU64},
+	PalletId,
+};
+
+pallet_nft_maker::nft_maker_mock! {
+	Fractions: pallet_nft_fractions::{Pallet, Call, Storage, Event<T>},
+}
+
+parameter_types! {
+	pub const FractionsCustody: PalletId = PalletId(*b"nftfract");
+	pub const ShareName: &'static str = "Fraction";
+	pub const ShareSymbol: &'static str = "FRAC";
+}
+
+impl pallet_nft_fractions::Config for Test {
+	type Event = Event;
+	type AssetId = u32;
+	type Assets = Assets;
+	type CollectionId = u32;
+	type ItemId = u32;
+	type Nfts = NftMaker;
+	type PalletId = FractionsCustody;
+	type FirstShareAssetId = ConstU32<100>;
+	type ShareName = ShareName;
+	type ShareSymbol = ShareSymbol;
+	type Currency = Balances;
+	type VaultDeposit = ConstU64<500>;
+}
+
+/// Create a collection owned by ALICE and mint an item of it to her
+pub fn mint_item() -> (u32, u32) {
+	let collection_id = create_collection(ALICE);
+	(collection_id, mint(ALICE, collection_id))
+}
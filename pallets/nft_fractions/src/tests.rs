@@ -0,0 +1,90 @@
+use crate::{mock::*, Error, Pallet, Vaults};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{
+		fungibles::{Inspect, InspectMetadata},
+		nonfungibles::Inspect as NftInspect,
+	},
+};
+use pallet_custom_traits::Lockable;
+
+#[test]
+fn fractionalize_mints_shares_of_a_new_asset() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = mint_item();
+		let reserved = Balances::reserved_balance(ALICE);
+
+		assert_ok!(Fractions::fractionalize(Origin::signed(ALICE), collection_id, item_id, 1_000));
+
+		assert_eq!(Assets::balance(100, &ALICE), 1_000);
+		assert_eq!(Assets::name(&100), b"Fraction".to_vec());
+		assert_eq!(Assets::symbol(&100), b"FRAC".to_vec());
+		assert_eq!(NftMaker::owner(&collection_id, &item_id), Some(Pallet::<Test>::account_id()));
+		assert!(NftMaker::is_locked(&collection_id, &item_id));
+		assert_eq!(Balances::reserved_balance(ALICE), reserved + 500);
+		assert!(Vaults::<Test>::contains_key(100));
+	});
+}
+
+#[test]
+fn fractionalize_skips_taken_asset_ids() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::create(Origin::signed(BOB), 100, BOB, 1));
+		let (collection_id, item_id) = mint_item();
+
+		assert_ok!(Fractions::fractionalize(Origin::signed(ALICE), collection_id, item_id, 10));
+
+		assert_eq!(Assets::balance(101, &ALICE), 10);
+		assert!(!Vaults::<Test>::contains_key(100));
+	});
+}
+
+#[test]
+fn fractionalize_requires_the_owner() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = mint_item();
+
+		assert_noop!(
+			Fractions::fractionalize(Origin::signed(BOB), collection_id, item_id, 10),
+			Error::<Test>::NotItemOwner
+		);
+		assert_noop!(
+			Fractions::fractionalize(Origin::signed(ALICE), collection_id, item_id, 0),
+			Error::<Test>::InvalidShares
+		);
+	});
+}
+
+#[test]
+fn redeem_returns_the_item_and_the_deposit() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = mint_item();
+		let reserved = Balances::reserved_balance(ALICE);
+		assert_ok!(Fractions::fractionalize(Origin::signed(ALICE), collection_id, item_id, 100));
+		assert_ok!(Assets::transfer(Origin::signed(ALICE), 100, BOB, 100));
+
+		assert_ok!(Fractions::redeem(Origin::signed(BOB), 100));
+
+		assert_eq!(NftMaker::owner(&collection_id, &item_id), Some(BOB));
+		assert!(!NftMaker::is_locked(&collection_id, &item_id));
+		assert_eq!(Assets::total_issuance(100), 0);
+		assert_eq!(Balances::reserved_balance(ALICE), reserved);
+		assert_eq!(Balances::free_balance(ALICE) + reserved, 1_000_000);
+		assert!(!Vaults::<Test>::contains_key(100));
+	});
+}
+
+#[test]
+fn redeem_requires_every_share() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = mint_item();
+		assert_ok!(Fractions::fractionalize(Origin::signed(ALICE), collection_id, item_id, 100));
+		assert_ok!(Assets::transfer(Origin::signed(ALICE), 100, BOB, 1));
+
+		assert_noop!(
+			Fractions::redeem(Origin::signed(ALICE), 100),
+			Error::<Test>::InsufficientShares
+		);
+		assert_noop!(Fractions::redeem(Origin::signed(ALICE), 101), Error::<Test>::VaultNotFound);
+	});
+}
@@ -0,0 +1,15 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// A NFT held in custody while its shares circulate
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, TypeInfo, MaxEncodedLen)]
+pub struct Vault<AccountId, CollectionId, ItemId, Balance, DepositBalance> {
+	/// Account that fractionalized the NFT, and gets the deposit back
+	pub(super) owner: AccountId,
+	pub(super) collection_id: CollectionId,
+	pub(super) item_id: ItemId,
+	/// Shares minted for the NFT, all of which are needed to redeem it
+	pub(super) shares: Balance,
+	/// Deposit reserved from the owner
+	pub(super) deposit: DepositBalance,
+}
@@ -0,0 +1,383 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod types;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		pallet_prelude::*,
+		storage::with_storage_layer,
+		traits::{
+			tokens::nonfungibles::{Inspect as NftInspect, Mutate as NftMutate},
+			Randomness,
+		},
+	};
+	use frame_system::pallet_prelude::*;
+	use pallet_custom_traits::Mint;
+	use sp_runtime::traits::{AtLeast32BitUnsigned, CheckedAdd, Hash, One, Saturating};
+
+	pub use crate::types::Drop;
+	use crate::types::{LootBox, Opening};
+
+	pub type AttributeOf<T> = (
+		BoundedVec<u8, <T as Config>::AttributeKeyLimit>,
+		BoundedVec<u8, <T as Config>::AttributeValueLimit>,
+	);
+	pub type DropOf<T> = Drop<BoundedVec<AttributeOf<T>, <T as Config>::MaxDropAttributes>>;
+	type LootBoxOf<T> = LootBox<
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::CollectionId,
+		BoundedVec<DropOf<T>, <T as Config>::MaxDrops>,
+	>;
+	type OpeningOf<T> = Opening<
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::CollectionId,
+		<T as frame_system::Config>::BlockNumber,
+		<T as frame_system::Config>::Hash,
+	>;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		type CollectionId: Member + Parameter + MaxEncodedLen + Copy;
+		type ItemId: Member + Parameter + MaxEncodedLen + Copy;
+		type OpeningId: Member + Parameter + MaxEncodedLen + Copy + AtLeast32BitUnsigned + Default;
+		type Nfts: NftInspect<Self::AccountId, CollectionId = Self::CollectionId, ItemId = Self::ItemId>
+			+ NftMutate<Self::AccountId>
+			+ Mint<Self::CollectionId, Self::ItemId, Self::AccountId>;
+
+		/// Source of the randomness loot is drawn with. It is sampled at the reveal block of an
+		/// opening and mixed with the secret the opener committed to, so block authors who can
+		/// bias it still can't pick the loot without the secret.
+		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+
+		/// Blocks between opening a box and the block whose randomness decides its loot
+		#[pallet::constant]
+		type RevealDelay: Get<Self::BlockNumber>;
+
+		/// Blocks after the reveal block during which the opener can reveal their secret and
+		/// claim. Openings that weren't claimed by then can be expired.
+		#[pallet::constant]
+		type ClaimPeriod: Get<Self::BlockNumber>;
+
+		/// Max length of the attribute keys of drops
+		#[pallet::constant]
+		type AttributeKeyLimit: Get<u32>;
+
+		/// Max length of the attribute values of drops
+		#[pallet::constant]
+		type AttributeValueLimit: Get<u32>;
+
+		/// Most drops in a drop table
+		#[pallet::constant]
+		type MaxDrops: Get<u32>;
+
+		/// Most attributes set on each drop
+		#[pallet::constant]
+		type MaxDropAttributes: Get<u32>;
+	}
+
+	/// Drop tables by the collection of their boxes. Every item of the collection is a box.
+	#[pallet::storage]
+	pub(super) type LootBoxes<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::CollectionId, LootBoxOf<T>, OptionQuery>;
+
+	#[pallet::storage]
+	pub(super) type Openings<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::OpeningId, OpeningOf<T>, OptionQuery>;
+
+	#[pallet::storage]
+	pub(super) type NextOpeningId<T: Config> = StorageValue<_, T::OpeningId, ValueQuery>;
+
+	/// Number of openings revealed at a block, until the block starts
+	#[pallet::storage]
+	pub(super) type PendingReveals<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+	/// (random seed, unclaimed openings) of the reveal blocks that passed
+	#[pallet::storage]
+	pub(super) type RevealSeeds<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, (T::Hash, u32), OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		// (box collection ID, owner)
+		LootBoxCreated(T::CollectionId, T::AccountId),
+		// (box collection ID)
+		LootBoxRemoved(T::CollectionId),
+		// (opening ID, opener, reveal block)
+		BoxOpened(T::OpeningId, T::AccountId, T::BlockNumber),
+		// (opening ID, opener, collection ID, item ID)
+		LootClaimed(T::OpeningId, T::AccountId, T::CollectionId, T::ItemId),
+		// (opening ID, opener, re-minted box item ID) when the loot couldn't be minted
+		BoxRefunded(T::OpeningId, T::AccountId, Option<T::ItemId>),
+		// (opening ID, opener)
+		OpeningExpired(T::OpeningId, T::AccountId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		NotCollectionOwner,
+		NoDrops,
+		LootBoxExists,
+		LootBoxNotFound,
+		NotLootBoxOwner,
+		OpeningsPending,
+		NotItemOwner,
+		OpeningNotFound,
+		NotRevealed,
+		Overflow,
+		InvalidSecret,
+		ClaimPeriodNotOver,
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Sample the randomness for the openings revealed at this block
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let openings = PendingReveals::<T>::take(now);
+			if openings == 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let (seed, _) = T::Randomness::random(LOOT_SUBJECT);
+			RevealSeeds::<T>::insert(now, (seed, openings));
+
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+	}
+
+	/// Subject of the randomness loot is drawn with
+	const LOOT_SUBJECT: &[u8] = b"nft_loot_boxes";
+
+	impl<T: Config> Pallet<T> {
+		/// Pick a drop with `seed`, with chances proportional to the drop weights. The roll comes
+		/// from the first 4 byte word of the seed below the largest multiple of the total weight,
+		/// rehashing the seed if there is none, so every roll is equally likely.
+		pub(crate) fn pick_drop(loot_box: &LootBoxOf<T>, seed: T::Hash) -> Option<&DropOf<T>> {
+			let total_weight = u64::from(loot_box.total_weight);
+			let words = 1u64 << 32;
+			let unbiased = words - words % total_weight;
+			let mut seed = seed;
+			let mut roll = 'draw: loop {
+				for word in seed.as_ref().chunks_exact(4) {
+					let random = u64::from(u32::decode(&mut &word[..]).ok()?);
+					if random < unbiased {
+						break 'draw (random % total_weight) as u32
+					}
+				}
+				seed = T::Hashing::hash(seed.as_ref());
+			};
+			for drop in loot_box.drops.iter() {
+				if roll < drop.weight {
+					return Some(drop)
+				}
+				roll -= drop.weight;
+			}
+			None
+		}
+
+		/// Mint an item with `attributes`, leaving no trace of it if any step fails
+		fn try_mint(
+			collection_id: &T::CollectionId,
+			owner: &T::AccountId,
+			attributes: &[AttributeOf<T>],
+		) -> Result<T::ItemId, DispatchError> {
+			with_storage_layer(|| {
+				let item_id = T::Nfts::mint(collection_id, owner)?;
+				for (key, value) in attributes {
+					T::Nfts::set_attribute(collection_id, &item_id, key, value)?;
+				}
+				Ok(item_id)
+			})
+		}
+
+		/// Remove a claimed or expired opening from its reveal block and its loot box
+		fn close_opening(
+			opening_id: T::OpeningId,
+			opening: &OpeningOf<T>,
+			(seed, unclaimed): (T::Hash, u32),
+		) {
+			Openings::<T>::remove(opening_id);
+			if unclaimed > 1 {
+				RevealSeeds::<T>::insert(opening.reveal_at, (seed, unclaimed - 1));
+			} else {
+				RevealSeeds::<T>::remove(opening.reveal_at);
+			}
+			LootBoxes::<T>::mutate(opening.box_collection_id, |loot_box| {
+				if let Some(loot_box) = loot_box {
+					loot_box.pending = loot_box.pending.saturating_sub(1);
+				}
+			});
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Make every item of `box_collection_id` a loot box that mints one of `drops` into
+		/// `collection_id` when opened. The caller must own both collections.
+		#[pallet::weight(5_000_000)]
+		pub fn create_loot_box(
+			origin: OriginFor<T>,
+			box_collection_id: T::CollectionId,
+			collection_id: T::CollectionId,
+			drops: BoundedVec<DropOf<T>, T::MaxDrops>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(
+				T::Nfts::collection_owner(&box_collection_id).as_ref() == Some(&sender) &&
+					T::Nfts::collection_owner(&collection_id).as_ref() == Some(&sender),
+				Error::<T>::NotCollectionOwner,
+			);
+			ensure!(!LootBoxes::<T>::contains_key(box_collection_id), Error::<T>::LootBoxExists);
+			let total_weight = drops
+				.iter()
+				.try_fold(0u32, |total, drop| total.checked_add(drop.weight))
+				.ok_or(Error::<T>::Overflow)?;
+			ensure!(total_weight > 0, Error::<T>::NoDrops);
+
+			let loot_box =
+				LootBox { owner: sender.clone(), collection_id, drops, total_weight, pending: 0 };
+			LootBoxes::<T>::insert(box_collection_id, loot_box);
+
+			Self::deposit_event(Event::<T>::LootBoxCreated(box_collection_id, sender));
+
+			Ok(())
+		}
+
+		/// Remove a drop table once every opened box of it has been claimed
+		#[pallet::weight(1_000_000)]
+		pub fn remove_loot_box(
+			origin: OriginFor<T>,
+			box_collection_id: T::CollectionId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let loot_box =
+				LootBoxes::<T>::get(box_collection_id).ok_or(Error::<T>::LootBoxNotFound)?;
+			ensure!(loot_box.owner == sender, Error::<T>::NotLootBoxOwner);
+			ensure!(loot_box.pending == 0, Error::<T>::OpeningsPending);
+
+			LootBoxes::<T>::remove(box_collection_id);
+
+			Self::deposit_event(Event::<T>::LootBoxRemoved(box_collection_id));
+
+			Ok(())
+		}
+
+		/// Burn a box, committing to the hash of a secret only the opener knows. Its loot is
+		/// decided by the secret and the randomness of the block `RevealDelay` blocks later, which
+		/// isn't known yet, and can be claimed with the secret from then on.
+		#[pallet::weight(5_000_000)]
+		pub fn open(
+			origin: OriginFor<T>,
+			box_collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			commitment: T::Hash,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut loot_box =
+				LootBoxes::<T>::get(box_collection_id).ok_or(Error::<T>::LootBoxNotFound)?;
+			ensure!(
+				T::Nfts::owner(&box_collection_id, &item_id).as_ref() == Some(&sender),
+				Error::<T>::NotItemOwner,
+			);
+
+			T::Nfts::burn(&box_collection_id, &item_id, Some(&sender))?;
+
+			let opening_id = NextOpeningId::<T>::get();
+			let next = opening_id.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
+			NextOpeningId::<T>::put(next);
+
+			let reveal_at = frame_system::Pallet::<T>::block_number()
+				.checked_add(&T::RevealDelay::get().max(One::one()))
+				.ok_or(Error::<T>::Overflow)?;
+			PendingReveals::<T>::mutate(reveal_at, |openings| {
+				*openings = openings.saturating_add(1)
+			});
+			loot_box.pending = loot_box.pending.checked_add(1).ok_or(Error::<T>::Overflow)?;
+			LootBoxes::<T>::insert(box_collection_id, loot_box);
+			let opening =
+				Opening { opener: sender.clone(), box_collection_id, reveal_at, commitment };
+			Openings::<T>::insert(opening_id, opening);
+
+			Self::deposit_event(Event::<T>::BoxOpened(opening_id, sender, reveal_at));
+
+			Ok(())
+		}
+
+		/// Mint the loot of an opened box to its opener once its reveal block has started,
+		/// revealing the secret the opener committed to. If the loot collection reached its max
+		/// supply, the box is minted back to the opener instead. Other failures, e.g. an opener
+		/// who can't pay the item deposit, fail the claim, which draws the same loot when retried.
+		#[pallet::weight(10_000_000)]
+		pub fn claim(
+			origin: OriginFor<T>,
+			opening_id: T::OpeningId,
+			secret: T::Hash,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let opening = Openings::<T>::get(opening_id).ok_or(Error::<T>::OpeningNotFound)?;
+			let reveal = RevealSeeds::<T>::get(opening.reveal_at).ok_or(Error::<T>::NotRevealed)?;
+			ensure!(T::Hashing::hash_of(&secret) == opening.commitment, Error::<T>::InvalidSecret);
+			let loot_box = LootBoxes::<T>::get(opening.box_collection_id)
+				.ok_or(Error::<T>::LootBoxNotFound)?;
+
+			let opening_seed = T::Hashing::hash_of(&(reveal.0, secret, opening_id));
+			let loot = Self::pick_drop(&loot_box, opening_seed).ok_or(Error::<T>::NoDrops)?;
+
+			Self::close_opening(opening_id, &opening, reveal);
+
+			if T::Nfts::is_full(&loot_box.collection_id) {
+				let box_item_id =
+					Self::try_mint(&opening.box_collection_id, &opening.opener, &[]).ok();
+				Self::deposit_event(Event::<T>::BoxRefunded(
+					opening_id,
+					opening.opener,
+					box_item_id,
+				));
+			} else {
+				let item_id =
+					Self::try_mint(&loot_box.collection_id, &opening.opener, &loot.attributes)?;
+				Self::deposit_event(Event::<T>::LootClaimed(
+					opening_id,
+					opening.opener,
+					loot_box.collection_id,
+					item_id,
+				));
+			}
+
+			Ok(())
+		}
+
+		/// Close an opening whose secret wasn't revealed within `ClaimPeriod` blocks of its
+		/// reveal block, so its loot box can be removed. The box isn't refunded, since openers
+		/// who withhold their secret already know what it would have dropped. Callable by anyone.
+		#[pallet::weight(1_000_000)]
+		pub fn expire(origin: OriginFor<T>, opening_id: T::OpeningId) -> DispatchResult {
+			ensure_signed(origin)?;
+			let opening = Openings::<T>::get(opening_id).ok_or(Error::<T>::OpeningNotFound)?;
+			let reveal = RevealSeeds::<T>::get(opening.reveal_at).ok_or(Error::<T>::NotRevealed)?;
+			let claimable_until = opening.reveal_at.saturating_add(T::ClaimPeriod::get());
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > claimable_until,
+				Error::<T>::ClaimPeriodNotOver,
+			);
+
+			Self::close_opening(opening_id, &opening, reveal);
+
+			Self::deposit_event(Event::<T>::OpeningExpired(opening_id, opening.opener));
+
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,40 @@
+use crate as pallet_nft_loot_boxes;
+use frame_support::traits::{ConstU32, ConstU64, Hooks, Randomness};
+use sp_core::H256;
+
+pallet_nft_maker::nft_maker_mock! {
+	NftLootBoxes: pallet_nft_loot_boxes::{Pallet, Call, Storage, Event<T>},
+}
+
+/// Randomness that is the number of the block it is sampled at
+pub struct BlockRandomness;
+
+impl Randomness<H256, u64> for BlockRandomness {
+	fn random(_subject: &[u8]) -> (H256, u64) {
+		let now = System::block_number();
+		(H256::from_low_u64_be(now), now)
+	}
+}
+
+impl pallet_nft_loot_boxes::Config for Test {
+	type Event = Event;
+	type CollectionId = u32;
+	type ItemId = u32;
+	type OpeningId = u32;
+	type Nfts = NftMaker;
+	type Randomness = BlockRandomness;
+	type RevealDelay = ConstU64<2>;
+	type ClaimPeriod = ConstU64<5>;
+	type AttributeKeyLimit = ConstU32<16>;
+	type AttributeValueLimit = ConstU32<32>;
+	type MaxDrops = ConstU32<4>;
+	type MaxDropAttributes = ConstU32<2>;
+}
+
+/// Start the blocks up to `n`, sampling the randomness of the openings revealed at them
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		NftLootBoxes::on_initialize(System::block_number());
+	}
+}
@@ -0,0 +1,182 @@
+use crate::{mock::*, Drop, DropOf, Error, LootBoxes, Openings};
+use frame_support::{assert_noop, assert_ok, traits::tokens::nonfungibles::Inspect as NftInspect};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+const SECRET: H256 = H256::repeat_byte(7);
+
+struct World {
+	boxes: u32,
+	swords: u32,
+}
+
+fn kind_drop(kind: &[u8], weight: u32) -> DropOf<Test> {
+	let attribute = (b"kind".to_vec().try_into().unwrap(), kind.to_vec().try_into().unwrap());
+	Drop { weight, attributes: vec![attribute].try_into().unwrap() }
+}
+
+fn sword_drop() -> DropOf<Test> {
+	kind_drop(b"sword", 1)
+}
+
+/// ALICE makes every item of `boxes` a loot box dropping swords, and gives one to BOB
+fn setup(max_swords: Option<u32>) -> (World, u32) {
+	let world = World {
+		boxes: create_collection(ALICE),
+		swords: create_collection_with_max_supply(ALICE, max_swords),
+	};
+	assert_ok!(NftLootBoxes::create_loot_box(
+		Origin::signed(ALICE),
+		world.boxes,
+		world.swords,
+		vec![sword_drop()].try_into().unwrap()
+	));
+	let box_item_id = mint(ALICE, world.boxes);
+	assert_ok!(NftMaker::transfer(Origin::signed(ALICE), world.boxes, box_item_id, BOB));
+	(world, box_item_id)
+}
+
+fn open(world: &World, box_item_id: u32) {
+	assert_ok!(NftLootBoxes::open(
+		Origin::signed(BOB),
+		world.boxes,
+		box_item_id,
+		BlakeTwo256::hash_of(&SECRET)
+	));
+}
+
+#[test]
+fn open_reveal_and_claim_mints_the_loot() {
+	new_test_ext().execute_with(|| {
+		let (world, box_item_id) = setup(None);
+
+		open(&world, box_item_id);
+		assert_eq!(NftMaker::owner(&world.boxes, &box_item_id), None);
+		run_to_block(3);
+		assert_ok!(NftLootBoxes::claim(Origin::signed(CHARLIE), 0, SECRET));
+
+		let sword = match last_event() {
+			Event::NftLootBoxes(crate::Event::LootClaimed(0, BOB, _, item_id)) => item_id,
+			event => panic!("Unexpected event {:?}", event),
+		};
+		assert_eq!(NftMaker::owner(&world.swords, &sword), Some(BOB));
+		assert_eq!(NftMaker::attribute(&world.swords, &sword, b"kind"), Some(b"sword".to_vec()));
+		assert!(!Openings::<Test>::contains_key(0));
+		assert_ok!(NftLootBoxes::remove_loot_box(Origin::signed(ALICE), world.boxes));
+	});
+}
+
+#[test]
+fn claim_requires_the_reveal_and_the_secret() {
+	new_test_ext().execute_with(|| {
+		let (world, box_item_id) = setup(None);
+		open(&world, box_item_id);
+
+		run_to_block(2);
+		assert_noop!(
+			NftLootBoxes::claim(Origin::signed(BOB), 0, SECRET),
+			Error::<Test>::NotRevealed
+		);
+		run_to_block(3);
+		assert_noop!(
+			NftLootBoxes::claim(Origin::signed(BOB), 0, H256::repeat_byte(8)),
+			Error::<Test>::InvalidSecret
+		);
+		assert_noop!(
+			NftLootBoxes::remove_loot_box(Origin::signed(ALICE), world.boxes),
+			Error::<Test>::OpeningsPending
+		);
+	});
+}
+
+#[test]
+fn claim_refunds_the_box_when_the_loot_cant_be_minted() {
+	new_test_ext().execute_with(|| {
+		let (world, box_item_id) = setup(Some(1));
+		mint(ALICE, world.swords);
+		open(&world, box_item_id);
+		run_to_block(3);
+
+		assert_ok!(NftLootBoxes::claim(Origin::signed(BOB), 0, SECRET));
+
+		let refunded = match last_event() {
+			Event::NftLootBoxes(crate::Event::BoxRefunded(0, BOB, Some(item_id))) => item_id,
+			event => panic!("Unexpected event {:?}", event),
+		};
+		assert_eq!(NftMaker::owner(&world.boxes, &refunded), Some(BOB));
+		assert_eq!(LootBoxes::<Test>::get(world.boxes).unwrap().pending, 0);
+		assert!(!Openings::<Test>::contains_key(0));
+	});
+}
+
+#[test]
+fn claim_fails_without_refunding_when_the_opener_cant_pay_the_deposit() {
+	new_test_ext().execute_with(|| {
+		let (world, box_item_id) = setup(None);
+		open(&world, box_item_id);
+		run_to_block(3);
+
+		assert_ok!(Balances::set_balance(Origin::root(), BOB, 1, 0));
+		assert_noop!(
+			NftLootBoxes::claim(Origin::signed(BOB), 0, SECRET),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert!(Openings::<Test>::contains_key(0));
+
+		assert_ok!(Balances::set_balance(Origin::root(), BOB, 1_000_000, 0));
+		assert_ok!(NftLootBoxes::claim(Origin::signed(BOB), 0, SECRET));
+		assert!(matches!(
+			last_event(),
+			Event::NftLootBoxes(crate::Event::LootClaimed(0, BOB, _, _))
+		));
+	});
+}
+
+#[test]
+fn drops_are_picked_in_proportion_to_their_weights() {
+	new_test_ext().execute_with(|| {
+		let boxes = create_collection(ALICE);
+		let gear = create_collection(ALICE);
+		let drops = vec![kind_drop(b"common", 3), kind_drop(b"never", 0), kind_drop(b"rare", 1)];
+		assert_ok!(NftLootBoxes::create_loot_box(
+			Origin::signed(ALICE),
+			boxes,
+			gear,
+			drops.try_into().unwrap()
+		));
+		let loot_box = LootBoxes::<Test>::get(boxes).unwrap();
+
+		let mut picks = [0u32; 3];
+		for i in 0u32..1_000 {
+			let drop = NftLootBoxes::pick_drop(&loot_box, BlakeTwo256::hash_of(&i)).unwrap();
+			let index = loot_box.drops.iter().position(|candidate| candidate == drop).unwrap();
+			picks[index] += 1;
+		}
+
+		assert_eq!(picks[1], 0);
+		assert_eq!(picks[0] + picks[2], 1_000);
+		assert!((700..800).contains(&picks[0]), "{:?}", picks);
+	});
+}
+
+#[test]
+fn expire_closes_openings_after_the_claim_period() {
+	new_test_ext().execute_with(|| {
+		let (world, box_item_id) = setup(None);
+		open(&world, box_item_id);
+
+		run_to_block(8);
+		assert_noop!(
+			NftLootBoxes::expire(Origin::signed(ALICE), 0),
+			Error::<Test>::ClaimPeriodNotOver
+		);
+		run_to_block(9);
+		assert_ok!(NftLootBoxes::expire(Origin::signed(ALICE), 0));
+
+		assert_noop!(
+			NftLootBoxes::claim(Origin::signed(BOB), 0, SECRET),
+			Error::<Test>::OpeningNotFound
+		);
+		assert_ok!(NftLootBoxes::remove_loot_box(Origin::signed(ALICE), world.boxes));
+	});
+}
@@ -0,0 +1,35 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::RuntimeDebug;
+use scale_info::TypeInfo;
+
+/// An entry of a drop table
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Drop<Attributes> {
+	/// Chance of the drop relative to the weights of the other drops of the table
+	pub weight: u32,
+	/// (key, value) attributes set on the minted item
+	pub attributes: Attributes,
+}
+
+#[derive(Clone, Encode, Decode, Eq, PartialEq, TypeInfo, MaxEncodedLen)]
+pub struct LootBox<AccountId, CollectionId, Drops> {
+	pub(super) owner: AccountId,
+	/// Collection the drops are minted into
+	pub(super) collection_id: CollectionId,
+	pub(super) drops: Drops,
+	/// Sum of the weights of the drops
+	pub(super) total_weight: u32,
+	/// Opened boxes whose loot hasn't been claimed yet
+	pub(super) pending: u32,
+}
+
+/// A box that was opened, waiting for the randomness of its reveal block and the opener's secret
+#[derive(Clone, Encode, Decode, Eq, PartialEq, TypeInfo, MaxEncodedLen)]
+pub struct Opening<AccountId, CollectionId, BlockNumber, Hash> {
+	pub(super) opener: AccountId,
+	/// Collection of the opened box
+	pub(super) box_collection_id: CollectionId,
+	pub(super) reveal_at: BlockNumber,
+	/// Hash of the secret the loot is drawn with
+	pub(super) commitment: Hash,
+}
@@ -0,0 +1,23 @@
+//! Runtime API definition for querying the NFTs an account owns and their metadata.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait NftApi<AccountId, CollectionId, ItemId> where
+		AccountId: Codec,
+		CollectionId: Codec,
+		ItemId: Codec,
+	{
+		/// Up to `limit` of the items `who` owns, skipping the first `start`
+		fn items_of(who: AccountId, start: u32, limit: u32) -> Vec<(CollectionId, ItemId)>;
+
+		/// The token URI of an item resolved to a URI clients can fetch, e.g. `ipfs://<cid>/1.json`
+		fn token_uri(collection_id: CollectionId, item_id: ItemId) -> Option<Vec<u8>>;
+
+		/// The Blake2-256 hash of an item's metadata JSON, to verify what `token_uri` points to
+		fn token_content_hash(collection_id: CollectionId, item_id: ItemId) -> Option<[u8; 32]>;
+	}
+}
@@ -0,0 +1,135 @@
+//! RPC interface for querying the NFTs an account owns and their metadata.
+
+use std::{fmt::Debug, marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+pub use pallet_nft_maker_rpc_runtime_api::NftApi as NftRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+#[rpc(client, server)]
+pub trait NftApi<BlockHash, AccountId, CollectionId, ItemId> {
+	/// Up to `limit` of the (collection ID, item ID) pairs `who` owns, skipping the first `start`
+	#[method(name = "nft_itemsOf")]
+	fn items_of(
+		&self,
+		who: AccountId,
+		start: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(CollectionId, ItemId)>>;
+
+	/// The token URI of an item, e.g. `ipfs://<cid>/1.json`, or `null` if it has none
+	#[method(name = "nft_tokenUri")]
+	fn token_uri(
+		&self,
+		collection_id: CollectionId,
+		item_id: ItemId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<String>>;
+
+	/// The Blake2-256 hash of an item's metadata JSON as `0x`-prefixed hex, or `null` if its
+	/// token URI has none
+	#[method(name = "nft_tokenContentHash")]
+	fn token_content_hash(
+		&self,
+		collection_id: CollectionId,
+		item_id: ItemId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<String>>;
+}
+
+/// Provides RPC methods to query the NFTs an account owns and their metadata.
+pub struct Nft<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Nft<C, Block> {
+	/// Creates a new instance of the Nft RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error codes of the Nft RPC
+pub enum Error {
+	/// The call to the runtime failed
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(e: impl Debug) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query the items",
+		Some(format!("{:?}", e)),
+	))
+	.into()
+}
+
+impl<C, Block, AccountId, CollectionId, ItemId>
+	NftApiServer<<Block as BlockT>::Hash, AccountId, CollectionId, ItemId> for Nft<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: NftRuntimeApi<Block, AccountId, CollectionId, ItemId>,
+	AccountId: Codec,
+	CollectionId: Codec,
+	ItemId: Codec,
+{
+	fn items_of(
+		&self,
+		who: AccountId,
+		start: u32,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(CollectionId, ItemId)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.items_of(&at, who, start, limit).map_err(runtime_error)
+	}
+
+	fn token_uri(
+		&self,
+		collection_id: CollectionId,
+		item_id: ItemId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<String>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let uri = api.token_uri(&at, collection_id, item_id).map_err(runtime_error)?;
+		Ok(uri.map(|uri| String::from_utf8_lossy(&uri).into_owned()))
+	}
+
+	fn token_content_hash(
+		&self,
+		collection_id: CollectionId,
+		item_id: ItemId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<String>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let hash = api.token_content_hash(&at, collection_id, item_id).map_err(runtime_error)?;
+		Ok(hash.map(|hash| {
+			let digits: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+			format!("0x{}", digits)
+		}))
+	}
+}
@@ -1,20 +1,59 @@
-//! Benchmarking setup for pallet-template
+//! Benchmarking setup for pallet-nft-maker
 
 use super::*;
 
-#[allow(unused)]
-use crate::Pallet as Template;
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use crate::{types::BatchTokenUris, Pallet as NftMaker};
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::{Currency, Get};
 use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+use sp_std::vec::Vec;
+
+const SEED: u32 = 0;
+
+fn create_collection<T: Config>(owner: &T::AccountId) -> T::CollectionId {
+	T::Currency::make_free_balance_be(owner, Bounded::max_value());
+	let collection_id = NextCollectionId::<T>::get();
+	NftMaker::<T>::do_create_collection(
+		collection_id,
+		owner.clone(),
+		Default::default(),
+		None,
+		MintPermission::Owner,
+		false,
+	)
+	.expect("Collection can be created");
+	collection_id
+}
+
+/// An HTTPS directory that leaves room for the longest item ID to be appended
+fn base_location<T: Config>() -> MetadataLocationOf<T> {
+	let len = T::TokenURILimit::get().saturating_sub(48) as usize;
+	let url = [&b"https://"[..], &sp_std::vec![b'a'; len], b"/"].concat();
+	MetadataLocation::Https(url.try_into().expect("Fits in the limit"))
+}
 
 benchmarks! {
-	do_something {
-		let s in 0 .. 100;
+	mint {
+		let caller: T::AccountId = whitelisted_caller();
+		let collection_id = create_collection::<T>(&caller);
+		let item_id = NextItemId::<T>::get();
+		let token_uri = TokenUri { location: base_location::<T>(), content_hash: Some([0; 32]) };
+	}: _(RawOrigin::Signed(caller), collection_id, token_uri, None)
+	verify {
+		assert!(Items::<T>::contains_key(collection_id, item_id));
+	}
+
+	mint_batch {
+		let n in 1 .. T::MaxBatchSize::get();
 		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller), s)
+		let collection_id = create_collection::<T>(&caller);
+		let recipients: Vec<T::AccountId> = (0..n).map(|i| account("recipient", i, SEED)).collect();
+		let token_uris = BatchTokenUris::Sequential(base_location::<T>());
+	}: _(RawOrigin::Signed(caller), collection_id, recipients, token_uris, None)
 	verify {
-		assert_eq!(Something::<T>::get(), Some(s));
+		assert_eq!(Collections::<T>::get(collection_id).map(|collection| collection.items), Some(n));
 	}
 
-	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
+	impl_benchmark_test_suite!(NftMaker, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -0,0 +1,114 @@
+//! Implementations of the `nonfungibles` traits, so other pallets can use NFT Maker through the
+//! standard interfaces. A single collection can be used through the `nonfungible` traits with
+//! `frame_support::traits::tokens::nonfungible::ItemOf`.
+
+use crate::{
+	pallet::{Attributes, Collections, Items},
+	AttributeKey, Config, Error, MintPermission, Pallet,
+};
+use frame_support::{
+	dispatch::DispatchResult,
+	ensure,
+	traits::tokens::nonfungibles::{Create, Inspect, Mutate, Transfer},
+	BoundedVec,
+};
+use pallet_custom_traits::Transfer as ItemTransfer;
+use sp_std::vec::Vec;
+
+impl<T: Config> Inspect<T::AccountId> for Pallet<T> {
+	type ItemId = T::ItemId;
+	type CollectionId = T::CollectionId;
+
+	fn owner(collection: &Self::CollectionId, item: &Self::ItemId) -> Option<T::AccountId> {
+		Items::<T>::get(collection, item).map(|item| item.owner)
+	}
+
+	fn collection_owner(collection: &Self::CollectionId) -> Option<T::AccountId> {
+		Collections::<T>::get(collection).map(|collection| collection.owner)
+	}
+
+	fn attribute(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		key: &[u8],
+	) -> Option<Vec<u8>> {
+		let key = AttributeKey::<T>::try_from(key.to_vec()).ok()?;
+		Attributes::<T>::get((collection, item, key)).map(|value| value.into_inner())
+	}
+
+	fn can_transfer(collection: &Self::CollectionId, item: &Self::ItemId) -> bool {
+		<Self as ItemTransfer<_, _, _>>::can_transfer(collection, item)
+	}
+}
+
+impl<T: Config> Create<T::AccountId> for Pallet<T> {
+	/// Create a collection owned by `who`, which only its owner can mint into. The collection
+	/// has a single owner, so `admin` is ignored. Its metadata is empty, so it holds no deposit.
+	fn create_collection(
+		collection: &Self::CollectionId,
+		who: &T::AccountId,
+		_admin: &T::AccountId,
+	) -> DispatchResult {
+		Self::do_create_collection(
+			*collection,
+			who.clone(),
+			Default::default(),
+			None,
+			MintPermission::Owner,
+			false,
+		)
+	}
+}
+
+impl<T: Config> Mutate<T::AccountId> for Pallet<T> {
+	/// Mint an item without a token URI and no royalty of its own. Other pallets decide who
+	/// may mint through this. The item deposit is reserved from `who`.
+	fn mint_into(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		who: &T::AccountId,
+	) -> DispatchResult {
+		Self::do_mint(*collection, *item, who.clone(), who.clone(), None, None, Some(who.clone()))
+	}
+
+	fn burn(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		maybe_check_owner: Option<&T::AccountId>,
+	) -> DispatchResult {
+		if let Some(check_owner) = maybe_check_owner {
+			let details = Items::<T>::get(collection, item).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(details.owner == *check_owner, Error::<T>::NotItemOwner);
+		}
+
+		Self::do_burn(*collection, *item)
+	}
+
+	/// Set an attribute of an item, reserving its deposit from the item's owner
+	fn set_attribute(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		key: &[u8],
+		value: &[u8],
+	) -> DispatchResult {
+		let key = AttributeKey::<T>::try_from(key.to_vec())
+			.map_err(|_| Error::<T>::AttributeKeyTooLong)?;
+		let value =
+			BoundedVec::try_from(value.to_vec()).map_err(|_| Error::<T>::AttributeValueTooLong)?;
+		let owner = Items::<T>::get(collection, item).ok_or(Error::<T>::ItemNotFound)?.owner;
+
+		Self::do_set_attribute(*collection, *item, key, value, Some(&owner))
+	}
+}
+
+impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		destination: &T::AccountId,
+	) -> DispatchResult {
+		let details = Items::<T>::get(collection, item).ok_or(Error::<T>::ItemNotFound)?;
+
+		Self::do_transfer(*collection, *item, details, destination.clone())
+	}
+}
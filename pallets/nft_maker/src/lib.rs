@@ -1,108 +1,1892 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod impl_nonfungibles;
+pub mod migrations;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+#[cfg(any(test, feature = "test-utils"))]
+mod test_utils;
+pub mod token_uri;
 mod types;
+pub mod weights;
 
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::pallet_prelude::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{fungibles, Currency, LockIdentifier, ReservableCurrency},
+	};
 	use frame_system::pallet_prelude::*;
-	use pallet_custom_traits::{Ownership, Transfer};
-	use sp_runtime::traits::{AccountIdConversion, AtLeast32BitUnsigned};
+	use pallet_custom_traits::{Lockable, Mint, OnBurn, Ownership, Rentable, Royalty, Transfer};
+	use sp_runtime::{
+		traits::{AtLeast32BitUnsigned, CheckedAdd, IdentifyAccount, One, Verify, Zero},
+		Permill, SaturatedConversion,
+	};
+	use sp_std::vec::Vec;
 
-	use crate::types::ItemDetails;
+	pub use crate::{
+		token_uri::{MetadataLocation, TokenUri},
+		types::{BatchTokenUris, MintPermission, MintVoucher},
+	};
+	use crate::{
+		types::{CollectionDetails, ItemDetails},
+		weights::WeightInfo,
+	};
+
+	type AssetIdOf<T: Config> = <T::Assets as fungibles::Inspect<T::AccountId>>::AssetId;
+	type BalanceOf<T: Config> = <T::Assets as fungibles::Inspect<T::AccountId>>::Balance;
+	type DepositBalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub type TokenUriOf<T> = TokenUri<<T as Config>::TokenURILimit>;
+	pub type MetadataLocationOf<T> = MetadataLocation<<T as Config>::TokenURILimit>;
+	pub type MintVoucherOf<T> = MintVoucher<
+		<T as Config>::CollectionId,
+		TokenUriOf<T>,
+		AssetIdOf<T>,
+		BalanceOf<T>,
+		<T as frame_system::Config>::BlockNumber,
+	>;
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
-		type ItemId: Member + Parameter + MaxEncodedLen + Copy + AtLeast32BitUnsigned;
+		type ItemId: Member + Parameter + MaxEncodedLen + Copy + AtLeast32BitUnsigned + Default;
+		type CollectionId: Member
+			+ Parameter
+			+ MaxEncodedLen
+			+ Copy
+			+ AtLeast32BitUnsigned
+			+ Default;
 
 		/// Max length for the tokenURI field
 		#[pallet::constant]
 		type TokenURILimit: Get<u32>;
 
-		// Unique key to count IDs for NFTs
+		/// Max length for the metadata URI of a collection
+		#[pallet::constant]
+		type CollectionMetadataLimit: Get<u32>;
+
+		/// Max length of an attribute key
+		#[pallet::constant]
+		type AttributeKeyLimit: Get<u32>;
+
+		/// Max length of an attribute value
+		#[pallet::constant]
+		type AttributeValueLimit: Get<u32>;
+
+		/// Most attributes an item can have
+		#[pallet::constant]
+		type MaxAttributes: Get<u32>;
+
+		/// Highest royalty of an item or collection, in basis points
+		#[pallet::constant]
+		type MaxRoyalty: Get<u16>;
+
+		/// Most levels of items that can be equipped into each other, e.g. 2 for a character
+		/// holding a bag holding a sword
+		#[pallet::constant]
+		type MaxNestingDepth: Get<u32>;
+
+		/// Most items that can be equipped directly into one item
+		#[pallet::constant]
+		type MaxChildren: Get<u32>;
+
+		/// Most items `mint_batch` can mint at once
 		#[pallet::constant]
-		type NFTMakerAccount: Get<frame_support::PalletId>;
+		type MaxBatchSize: Get<u32>;
+
+		/// Currency storage deposits are reserved in
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Deposit reserved from the minter of each item
+		#[pallet::constant]
+		type ItemDeposit: Get<DepositBalanceOf<Self>>;
+
+		/// Deposit reserved per byte of token URIs, attributes and collection metadata
+		#[pallet::constant]
+		type DepositPerByte: Get<DepositBalanceOf<Self>>;
+
+		/// May change the deposits of a collection, e.g. to lower them for verified collections
+		type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Assets vouchers are paid with
+		type Assets: fungibles::Transfer<Self::AccountId>;
+
+		/// Signature collection owners sign mint vouchers with
+		type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+
+		/// Public key of a voucher signature, identifying the collection owner
+		type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+
+		/// Notified of burned items
+		type OnBurn: OnBurn<Self::CollectionId, Self::ItemId>;
+
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::storage]
-	pub(super) type Items<T: Config> = StorageMap<
+	pub(super) type Collections<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::CollectionId,
+		CollectionDetails<T::AccountId, T::CollectionMetadataLimit>,
+		OptionQuery,
+	>;
+
+	/// Accounts that may mint into a collection with `MintPermission::Minters`
+	#[pallet::storage]
+	pub(super) type Minters<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::CollectionId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Game accounts a collection owner authorized to manage the collection's items, e.g. to burn
+	/// consumed items
+	#[pallet::storage]
+	pub(super) type GameAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::CollectionId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Game authorities, e.g. game servers, and the attribute keys they may change on any item of
+	/// a collection, as (collection ID, account, attribute key)
+	#[pallet::storage]
+	pub(super) type GameAuthorities<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::CollectionId>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, AttributeKey<T>>,
+		),
+		(),
+		OptionQuery,
+	>;
+
+	/// The account approved to transfer an item on its owner's behalf
+	#[pallet::storage]
+	pub(super) type Approvals<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::CollectionId,
+		Blake2_128Concat,
+		T::ItemId,
+		T::AccountId,
+		OptionQuery,
+	>;
+
+	/// (owner, operator) pairs. Operators may transfer every item of the owner.
+	#[pallet::storage]
+	pub(super) type Operators<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	pub(super) type NextCollectionId<T: Config> = StorageValue<_, T::CollectionId, ValueQuery>;
+
+	#[pallet::storage]
+	pub(super) type Items<T: Config> = StorageDoubleMap<
 		_,
 		Blake2_128Concat,
+		T::CollectionId,
+		Blake2_128Concat,
 		T::ItemId,
 		ItemDetails<T::AccountId, T::TokenURILimit>,
 		OptionQuery,
 	>;
 
+	/// On-chain attributes of an item, e.g. `level` or `durability`
+	#[pallet::storage]
+	pub(super) type Attributes<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::CollectionId>,
+			NMapKey<Blake2_128Concat, T::ItemId>,
+			NMapKey<Blake2_128Concat, AttributeKey<T>>,
+		),
+		BoundedVec<u8, T::AttributeValueLimit>,
+		OptionQuery,
+	>;
+
+	/// Locks other pallets hold on items. Locked items can't be transferred or burned.
+	#[pallet::storage]
+	pub(super) type Locks<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::CollectionId>,
+			NMapKey<Blake2_128Concat, T::ItemId>,
+			NMapKey<Blake2_128Concat, LockIdentifier>,
+		),
+		(),
+		OptionQuery,
+	>;
+
+	/// The item each equipped item is equipped into, as (collection ID, item ID)
+	#[pallet::storage]
+	pub(super) type Parents<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(T::CollectionId, T::ItemId),
+		(T::CollectionId, T::ItemId),
+		OptionQuery,
+	>;
+
+	/// Items equipped into each item, as parent -> child
+	#[pallet::storage]
+	pub(super) type Children<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(T::CollectionId, T::ItemId),
+		Blake2_128Concat,
+		(T::CollectionId, T::ItemId),
+		(),
+		OptionQuery,
+	>;
+
+	/// The account using an item other than its owner, e.g. a renter, and the block its use
+	/// expires at
+	#[pallet::storage]
+	pub(super) type Users<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(T::CollectionId, T::ItemId),
+		(T::AccountId, T::BlockNumber),
+		OptionQuery,
+	>;
+
+	/// Items whose user expires at a block, as block -> (collection ID, item ID)
+	#[pallet::storage]
+	pub(super) type UserExpiries<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		(T::CollectionId, T::ItemId),
+		(),
+		OptionQuery,
+	>;
+
+	/// The first block whose user expiries `on_idle` hasn't cleaned up yet. Unset until the first
+	/// user is set.
+	#[pallet::storage]
+	pub(super) type ExpiryCursor<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// Deposits of the items, as (depositor, amount). Whoever last wrote an item's token URI
+	/// holds the deposit for the item and its token URI.
+	#[pallet::storage]
+	pub(super) type ItemDeposits<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(T::CollectionId, T::ItemId),
+		(T::AccountId, DepositBalanceOf<T>),
+		OptionQuery,
+	>;
+
+	/// Deposits of the attributes, as (depositor, amount), held by whoever last set them
+	#[pallet::storage]
+	pub(super) type AttributeDeposits<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::CollectionId>,
+			NMapKey<Blake2_128Concat, T::ItemId>,
+			NMapKey<Blake2_128Concat, AttributeKey<T>>,
+		),
+		(T::AccountId, DepositBalanceOf<T>),
+		OptionQuery,
+	>;
+
+	/// Deposits the collection owners hold for their collection metadata
+	#[pallet::storage]
+	pub(super) type CollectionDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::CollectionId, DepositBalanceOf<T>, OptionQuery>;
+
+	/// (item deposit, deposit per byte) of collections that don't use the default deposits
+	#[pallet::storage]
+	pub(super) type DepositOverrides<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::CollectionId,
+		(DepositBalanceOf<T>, DepositBalanceOf<T>),
+		OptionQuery,
+	>;
+
+	/// Index of the items each account owns, as owner -> (collection ID, item ID)
+	#[pallet::storage]
+	pub(super) type AccountItems<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		(T::CollectionId, T::ItemId),
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	pub(super) type NextItemId<T: Config> = StorageValue<_, T::ItemId, ValueQuery>;
+
+	/// Nonces of the redeemed or canceled mint vouchers of each collection
 	#[pallet::storage]
-	pub(super) type Counter<T: Config> =
-		CountedStorageMap<_, Twox128, T::AccountId, T::ItemId, OptionQuery>;
+	pub(super) type UsedVouchers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::CollectionId,
+		Blake2_128Concat,
+		u64,
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// (collection ID, owner, metadata, max supply) of the collections to create
+		pub collections: Vec<(T::CollectionId, T::AccountId, Vec<u8>, Option<u32>)>,
+		/// (collection ID, item ID, owner, token URI) of the items to mint
+		pub items: Vec<(T::CollectionId, T::ItemId, T::AccountId, Vec<u8>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { collections: Default::default(), items: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (collection_id, owner, metadata, max_supply) in &self.collections {
+				let metadata =
+					metadata.clone().try_into().expect("Collection metadata is too long");
+				Pallet::<T>::do_create_collection(
+					*collection_id,
+					owner.clone(),
+					metadata,
+					*max_supply,
+					MintPermission::Owner,
+					false,
+				)
+				.expect("Invalid genesis collection");
+			}
+
+			for (collection_id, item_id, owner, token_uri) in &self.items {
+				let location =
+					MetadataLocation::from_uri(token_uri).expect("Invalid genesis token URI");
+				Pallet::<T>::do_mint(
+					*collection_id,
+					*item_id,
+					owner.clone(),
+					owner.clone(),
+					Some(TokenUri { location, content_hash: None }),
+					None,
+					None,
+				)
+				.expect("Invalid genesis item");
+			}
+		}
+	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		NftMinted(T::ItemId, T::AccountId),
+		// (collection ID, owner)
+		CollectionCreated(T::CollectionId, T::AccountId),
+		// (collection ID, permission)
+		MintPermissionSet(T::CollectionId, MintPermission),
+		// (collection ID, account, is minter)
+		MinterSet(T::CollectionId, T::AccountId, bool),
+		// (collection ID, account, is game account)
+		GameAccountSet(T::CollectionId, T::AccountId, bool),
+		// (collection ID, account, attribute key, is game authority)
+		GameAuthoritySet(T::CollectionId, T::AccountId, AttributeKey<T>, bool),
+		// (collection ID, item ID, owner)
+		NftMinted(T::CollectionId, T::ItemId, T::AccountId),
+		// (collection ID, first item ID, number of items)
+		BatchMinted(T::CollectionId, T::ItemId, u32),
+		// (collection ID, item ID, owner)
+		NftBurned(T::CollectionId, T::ItemId, T::AccountId),
+		// (collection ID)
+		CollectionDestroyed(T::CollectionId),
+		// (collection ID, item ID, from, to)
+		Transferred(T::CollectionId, T::ItemId, T::AccountId, T::AccountId),
+		// (collection ID, item ID, owner, delegate)
+		ApprovalSet(T::CollectionId, T::ItemId, T::AccountId, T::AccountId),
+		// (collection ID, item ID, owner)
+		ApprovalCanceled(T::CollectionId, T::ItemId, T::AccountId),
+		// (owner, operator, is operator)
+		OperatorSet(T::AccountId, T::AccountId, bool),
+		// (collection ID, basis points)
+		CollectionRoyaltySet(T::CollectionId, u16),
+		// (collection ID, item ID, key, value)
+		AttributeSet(
+			T::CollectionId,
+			T::ItemId,
+			AttributeKey<T>,
+			BoundedVec<u8, T::AttributeValueLimit>,
+		),
+		// (collection ID, item ID, key)
+		AttributeCleared(T::CollectionId, T::ItemId, AttributeKey<T>),
+		// (collection ID, item ID, key, new value or None when cleared, game authority)
+		AttributeChangedByAuthority(
+			T::CollectionId,
+			T::ItemId,
+			AttributeKey<T>,
+			Option<BoundedVec<u8, T::AttributeValueLimit>>,
+			T::AccountId,
+		),
+		// (collection ID, item ID)
+		TokenUriSet(T::CollectionId, T::ItemId),
+		// (collection ID, item ID, token URI frozen, attributes frozen)
+		ItemFrozen(T::CollectionId, T::ItemId, bool, bool),
+		// (collection ID, item ID or `None` for the whole collection)
+		SoulboundSet(T::CollectionId, Option<T::ItemId>),
+		// (collection ID, item ID, lock ID)
+		ItemLocked(T::CollectionId, T::ItemId, LockIdentifier),
+		// (collection ID, item ID, lock ID)
+		ItemUnlocked(T::CollectionId, T::ItemId, LockIdentifier),
+		// (collection ID, item ID, parent collection ID, parent item ID)
+		ItemEquipped(T::CollectionId, T::ItemId, T::CollectionId, T::ItemId),
+		// (collection ID, item ID, parent collection ID, parent item ID)
+		ItemUnequipped(T::CollectionId, T::ItemId, T::CollectionId, T::ItemId),
+		// (collection ID, item ID, user, expires at)
+		UserSet(T::CollectionId, T::ItemId, T::AccountId, T::BlockNumber),
+		// (collection ID, item ID, user)
+		UserExpired(T::CollectionId, T::ItemId, T::AccountId),
+		// (collection ID, (item deposit, deposit per byte), or None for the defaults)
+		CollectionDepositsSet(T::CollectionId, Option<(DepositBalanceOf<T>, DepositBalanceOf<T>)>),
+		// (collection ID, item ID, redeemer, voucher nonce)
+		VoucherRedeemed(T::CollectionId, T::ItemId, T::AccountId, u64),
+		// (collection ID, voucher nonce)
+		VoucherCanceled(T::CollectionId, u64),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		CollectionNotFound,
+		NotCollectionOwner,
+		NoMintPermission,
+		MaxSupplyReached,
+		Overflow,
+		ItemNotFound,
+		NoBurnPermission,
+		CollectionNotEmpty,
+		NotItemOwner,
+		NotApproved,
+		ApprovalNotFound,
+		RoyaltyTooHigh,
+		NoMetadataPermission,
+		TokenUriFrozen,
+		AttributesFrozen,
+		TooManyAttributes,
+		AttributeNotFound,
+		CollectionExists,
+		ItemExists,
+		AttributeKeyTooLong,
+		AttributeValueTooLong,
+		Soulbound,
+		Locked,
+		AlreadyLocked,
+		LockNotFound,
+		Equipped,
+		NotEquipped,
+		HasChildren,
+		NestingCycle,
+		NestingTooDeep,
+		TooManyChildren,
+		InvalidSignature,
+		VoucherUsed,
+		EmptyBatch,
+		BatchTooLarge,
+		TokenUriCountMismatch,
+		TokenUriTooLong,
+		InvalidExpiry,
+		UserActive,
+		InvalidTokenUri,
+		VoucherExpired,
 	}
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
-	impl<T: Config> Ownership<T::ItemId, T::AccountId> for Pallet<T> {
-		fn is_owner(id: &T::ItemId, who: &T::AccountId) -> bool {
-			let item = Items::<T>::get(id);
-			if !item.is_some() {
-				return false
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Remove expired users with the block's leftover weight
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let db = T::DbWeight::get();
+			let mut used = db.reads_writes(1, 1);
+			if used > remaining_weight {
+				return 0
+			}
+			let mut block = match ExpiryCursor::<T>::get() {
+				Some(block) => block,
+				None => return db.reads(1),
+			};
+
+			let per_block = db.reads(1);
+			let per_expiry = db.reads_writes(1, 2);
+			'blocks: while block <= now && used.saturating_add(per_block) <= remaining_weight {
+				used = used.saturating_add(per_block);
+				let expiries: Vec<_> = UserExpiries::<T>::iter_key_prefix(block).collect();
+				for (collection_id, item_id) in expiries {
+					if used.saturating_add(per_expiry) > remaining_weight {
+						break 'blocks
+					}
+					used = used.saturating_add(per_expiry);
+					Self::expire_user(block, collection_id, item_id);
+				}
+				block += One::one();
 			}
+			ExpiryCursor::<T>::put(block);
+
+			used
+		}
+	}
 
-			item.unwrap().owner == *who
+	pub type AttributeKey<T> = BoundedVec<u8, <T as Config>::AttributeKeyLimit>;
+
+	/// Most items `items_of` returns per page
+	const MAX_ITEMS_PAGE: u32 = 1_000;
+
+	impl<T: Config> Ownership<T::CollectionId, T::ItemId, T::AccountId> for Pallet<T> {
+		/// Equipped items always have the owner of the item at the root of their loadout, since
+		/// `move_item` moves them along with it, so their own owner is checked
+		fn is_owner(collection_id: &T::CollectionId, id: &T::ItemId, who: &T::AccountId) -> bool {
+			Items::<T>::get(collection_id, id).map_or(false, |item| item.owner == *who)
 		}
 	}
 
-	impl<T: Config> Transfer<T::ItemId, T::AccountId> for Pallet<T> {
-		fn transfer(id: &T::ItemId, to: &T::AccountId) -> bool {
-			let item = Items::<T>::get(id);
+	impl<T: Config> Transfer<T::CollectionId, T::ItemId, T::AccountId> for Pallet<T> {
+		fn transfer(collection_id: &T::CollectionId, id: &T::ItemId, to: &T::AccountId) -> bool {
+			let item = Items::<T>::get(collection_id, id);
 			if item.is_none() {
 				return false
 			}
-			let mut item = item.unwrap();
 
-			item.owner = to.clone();
-			Items::<T>::insert(id, &item);
+			Self::do_transfer(*collection_id, *id, item.unwrap(), to.clone()).is_ok()
+		}
 
-			true
+		fn can_transfer(collection_id: &T::CollectionId, id: &T::ItemId) -> bool {
+			match Items::<T>::get(collection_id, id) {
+				Some(item) => Self::ensure_transferable(*collection_id, *id, &item).is_ok(),
+				None => false,
+			}
 		}
 	}
 
-	impl<T: Config> Pallet<T> {
-		fn account_id() -> T::AccountId {
-			T::NFTMakerAccount::get().into_account_truncating()
+	impl<T: Config> Lockable<T::CollectionId, T::ItemId> for Pallet<T> {
+		fn lock(
+			collection_id: &T::CollectionId,
+			id: &T::ItemId,
+			lock_id: LockIdentifier,
+		) -> DispatchResult {
+			ensure!(Items::<T>::contains_key(collection_id, id), Error::<T>::ItemNotFound);
+			ensure!(
+				!Locks::<T>::contains_key((collection_id, id, lock_id)),
+				Error::<T>::AlreadyLocked,
+			);
+
+			Locks::<T>::insert((collection_id, id, lock_id), ());
+
+			Self::deposit_event(Event::<T>::ItemLocked(*collection_id, *id, lock_id));
+
+			Ok(())
+		}
+
+		fn unlock(
+			collection_id: &T::CollectionId,
+			id: &T::ItemId,
+			lock_id: LockIdentifier,
+		) -> DispatchResult {
+			ensure!(
+				Locks::<T>::contains_key((collection_id, id, lock_id)),
+				Error::<T>::LockNotFound,
+			);
+
+			Locks::<T>::remove((collection_id, id, lock_id));
+
+			Self::deposit_event(Event::<T>::ItemUnlocked(*collection_id, *id, lock_id));
+
+			Ok(())
 		}
 
-		fn increment_ids() -> T::ItemId {
-			let key = Self::account_id();
-			let result = Counter::<T>::get(&key).unwrap();
-			let next = result + 1u32.into();
-			Counter::<T>::set(key, next.into());
-			next
+		fn is_locked(collection_id: &T::CollectionId, id: &T::ItemId) -> bool {
+			Locks::<T>::iter_key_prefix((collection_id, id)).next().is_some()
+		}
+	}
+
+	impl<T: Config> Rentable<T::CollectionId, T::ItemId, T::AccountId, T::BlockNumber> for Pallet<T> {
+		fn user(collection_id: &T::CollectionId, id: &T::ItemId) -> Option<T::AccountId> {
+			Self::user_of(*collection_id, *id)
+		}
+
+		fn set_user(
+			collection_id: &T::CollectionId,
+			id: &T::ItemId,
+			user: &T::AccountId,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			ensure!(Items::<T>::contains_key(collection_id, id), Error::<T>::ItemNotFound);
+			Self::do_set_user(*collection_id, *id, user.clone(), expires_at)
+		}
+	}
+
+	impl<T: Config> Mint<T::CollectionId, T::ItemId, T::AccountId> for Pallet<T> {
+		/// Mint an item without a token URI, created by the collection owner. Other pallets
+		/// decide who may mint through this. The item deposit is reserved from `owner`, who
+		/// asked for the item, e.g. by crafting it.
+		fn mint(
+			collection_id: &T::CollectionId,
+			owner: &T::AccountId,
+		) -> Result<T::ItemId, DispatchError> {
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			let item_id = NextItemId::<T>::get();
+			Self::do_mint(
+				*collection_id,
+				item_id,
+				owner.clone(),
+				collection.owner,
+				None,
+				None,
+				Some(owner.clone()),
+			)?;
+			Ok(item_id)
+		}
+
+		fn is_full(collection_id: &T::CollectionId) -> bool {
+			Collections::<T>::get(collection_id).map_or(false, |collection| {
+				collection.max_supply.map_or(false, |max_supply| collection.items >= max_supply)
+			})
+		}
+	}
+
+	impl<T: Config> Royalty<T::CollectionId, T::ItemId, T::AccountId> for Pallet<T> {
+		fn royalty(
+			collection_id: &T::CollectionId,
+			id: &T::ItemId,
+		) -> Option<(T::AccountId, Permill)> {
+			let item = Items::<T>::get(collection_id, id)?;
+			let (recipient, basis_points) = match item.royalty {
+				Some(basis_points) => (item.creator, basis_points),
+				None => {
+					let collection = Collections::<T>::get(collection_id)?;
+					(collection.owner, collection.royalty)
+				},
+			};
+			if basis_points == 0 {
+				return None
+			}
+
+			Some((recipient, Permill::from_parts(u32::from(basis_points) * 100)))
 		}
 	}
 
-	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(5_000_000)]
-		pub fn mint(
-			origin: OriginFor<T>,
-			token_uri: BoundedVec<u8, T::TokenURILimit>,
+		/// Create a collection with a given ID. IDs from `NextCollectionId` on are skipped by
+		/// `create_collection` once used.
+		pub(crate) fn do_create_collection(
+			collection_id: T::CollectionId,
+			owner: T::AccountId,
+			metadata: BoundedVec<u8, T::CollectionMetadataLimit>,
+			max_supply: Option<u32>,
+			mint_permission: MintPermission,
+			reserve_deposit: bool,
 		) -> DispatchResult {
-			let owner = ensure_signed(origin)?;
-			let next_id = Self::increment_ids();
-			let nft = ItemDetails { owner: owner.clone(), token_uri };
+			ensure!(!Collections::<T>::contains_key(collection_id), Error::<T>::CollectionExists);
+			if collection_id >= NextCollectionId::<T>::get() {
+				let next = collection_id.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
+				NextCollectionId::<T>::put(next);
+			}
+
+			let collection = CollectionDetails {
+				owner: owner.clone(),
+				metadata,
+				max_supply,
+				items: 0,
+				mint_permission,
+				royalty: 0,
+				soulbound: false,
+			};
+			let depositor = if reserve_deposit { Some(&owner) } else { None };
+			let deposit = Self::byte_deposit(collection_id, collection.metadata.len());
+			if let Some((_, deposit)) = Self::redeposit(None, depositor, deposit)? {
+				CollectionDeposits::<T>::insert(collection_id, deposit);
+			}
+			Collections::<T>::insert(collection_id, collection);
+
+			Self::deposit_event(Event::<T>::CollectionCreated(collection_id, owner));
+
+			Ok(())
+		}
+
+		/// Mint an item with a given ID to `owner`, recording `creator` as its creator and
+		/// reserving its deposit from `depositor`. IDs from `NextItemId` on are skipped by `mint`
+		/// once used. Doesn't check mint permissions.
+		pub(crate) fn do_mint(
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			owner: T::AccountId,
+			creator: T::AccountId,
+			token_uri: Option<TokenUriOf<T>>,
+			royalty: Option<u16>,
+			depositor: Option<T::AccountId>,
+		) -> DispatchResult {
+			if let Some(basis_points) = royalty {
+				ensure!(basis_points <= T::MaxRoyalty::get(), Error::<T>::RoyaltyTooHigh);
+			}
+			if let Some(token_uri) = &token_uri {
+				ensure!(token_uri.location.is_valid(), Error::<T>::InvalidTokenUri);
+			}
+			ensure!(!Items::<T>::contains_key(collection_id, item_id), Error::<T>::ItemExists);
+			let mut collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			if let Some(max_supply) = collection.max_supply {
+				ensure!(collection.items < max_supply, Error::<T>::MaxSupplyReached);
+			}
+			collection.items = collection.items.checked_add(1).ok_or(Error::<T>::Overflow)?;
+			if item_id >= NextItemId::<T>::get() {
+				let next = item_id.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
+				NextItemId::<T>::put(next);
+			}
+
+			let deposit = Self::item_deposit(collection_id, token_uri.encoded_size());
+			if let Some(deposit) = Self::redeposit(None, depositor.as_ref(), deposit)? {
+				ItemDeposits::<T>::insert((collection_id, item_id), deposit);
+			}
+
+			let nft = ItemDetails {
+				owner: owner.clone(),
+				token_uri,
+				creator,
+				royalty,
+				attributes: 0,
+				uri_frozen: false,
+				attributes_frozen: false,
+				soulbound: false,
+			};
+			Items::<T>::insert(collection_id, item_id, nft);
+			AccountItems::<T>::insert(&owner, (collection_id, item_id), ());
+			Collections::<T>::insert(collection_id, collection);
+
+			Self::deposit_event(Event::<T>::NftMinted(collection_id, item_id, owner));
+
+			Ok(())
+		}
+
+		/// Burn an item and remove everything that references it. Items with an active user, e.g.
+		/// a paid rental, can't be burned until the use expires. Doesn't check permissions.
+		pub(crate) fn do_burn(
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+		) -> DispatchResult {
+			ensure!(!Self::is_locked(&collection_id, &item_id), Error::<T>::Locked);
+			ensure!(Self::user_of(collection_id, item_id).is_none(), Error::<T>::UserActive);
+			ensure!(!Parents::<T>::contains_key((collection_id, item_id)), Error::<T>::Equipped);
+			ensure!(
+				Children::<T>::iter_key_prefix((collection_id, item_id)).next().is_none(),
+				Error::<T>::HasChildren,
+			);
+			let item = Items::<T>::take(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			Approvals::<T>::remove(collection_id, item_id);
+			Users::<T>::remove((collection_id, item_id));
+			AccountItems::<T>::remove(&item.owner, (collection_id, item_id));
+			let _ = Attributes::<T>::clear_prefix((collection_id, item_id), u32::MAX, None);
+			if let Some((depositor, deposit)) = ItemDeposits::<T>::take((collection_id, item_id)) {
+				T::Currency::unreserve(&depositor, deposit);
+			}
+			let attribute_deposits = AttributeDeposits::<T>::drain_prefix((collection_id, item_id));
+			for (_, (depositor, deposit)) in attribute_deposits {
+				T::Currency::unreserve(&depositor, deposit);
+			}
+			Collections::<T>::mutate(collection_id, |collection| {
+				if let Some(collection) = collection {
+					collection.items = collection.items.saturating_sub(1);
+				}
+			});
+			T::OnBurn::on_burn(&collection_id, &item_id);
+
+			Self::deposit_event(Event::<T>::NftBurned(collection_id, item_id, item.owner));
+
+			Ok(())
+		}
+
+		/// Set an attribute of an item unless its attributes are frozen, reserving its deposit
+		/// from `depositor`. Doesn't check permissions.
+		pub(crate) fn do_set_attribute(
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			key: AttributeKey<T>,
+			value: BoundedVec<u8, T::AttributeValueLimit>,
+			depositor: Option<&T::AccountId>,
+		) -> DispatchResult {
+			let mut item =
+				Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(!item.attributes_frozen, Error::<T>::AttributesFrozen);
+
+			if !Attributes::<T>::contains_key((collection_id, item_id, &key)) {
+				ensure!(item.attributes < T::MaxAttributes::get(), Error::<T>::TooManyAttributes);
+				item.attributes += 1;
+				Items::<T>::insert(collection_id, item_id, item);
+			}
+			let old_deposit = AttributeDeposits::<T>::take((collection_id, item_id, &key));
+			let deposit = Self::byte_deposit(collection_id, key.len() + value.len());
+			if let Some(deposit) = Self::redeposit(old_deposit, depositor, deposit)? {
+				AttributeDeposits::<T>::insert((collection_id, item_id, &key), deposit);
+			}
+			Attributes::<T>::insert((collection_id, item_id, &key), &value);
+
+			Self::deposit_event(Event::<T>::AttributeSet(collection_id, item_id, key, value));
+
+			Ok(())
+		}
+
+		/// (item deposit, deposit per byte) of a collection
+		fn deposit_terms(
+			collection_id: T::CollectionId,
+		) -> (DepositBalanceOf<T>, DepositBalanceOf<T>) {
+			DepositOverrides::<T>::get(collection_id)
+				.unwrap_or_else(|| (T::ItemDeposit::get(), T::DepositPerByte::get()))
+		}
+
+		/// Deposit for an item with a `uri_len` bytes long token URI
+		fn item_deposit(collection_id: T::CollectionId, uri_len: usize) -> DepositBalanceOf<T> {
+			let (item_deposit, _) = Self::deposit_terms(collection_id);
+			item_deposit.saturating_add(Self::byte_deposit(collection_id, uri_len))
+		}
+
+		/// Deposit for `len` bytes of metadata
+		fn byte_deposit(collection_id: T::CollectionId, len: usize) -> DepositBalanceOf<T> {
+			let (_, per_byte) = Self::deposit_terms(collection_id);
+			per_byte.saturating_mul((len as u32).into())
+		}
+
+		/// Release a previous deposit and reserve `amount` from `depositor` in its place. Returns
+		/// the new deposit to store, if any.
+		fn redeposit(
+			old: Option<(T::AccountId, DepositBalanceOf<T>)>,
+			depositor: Option<&T::AccountId>,
+			amount: DepositBalanceOf<T>,
+		) -> Result<Option<(T::AccountId, DepositBalanceOf<T>)>, DispatchError> {
+			if let Some((old_depositor, old_amount)) = old {
+				T::Currency::unreserve(&old_depositor, old_amount);
+			}
+
+			match depositor {
+				Some(depositor) if !amount.is_zero() => {
+					T::Currency::reserve(depositor, amount)?;
+					Ok(Some((depositor.clone(), amount)))
+				},
+				_ => Ok(None),
+			}
+		}
+
+		fn can_mint(
+			collection_id: T::CollectionId,
+			collection: &CollectionDetails<T::AccountId, T::CollectionMetadataLimit>,
+			who: &T::AccountId,
+		) -> bool {
+			if collection.owner == *who {
+				return true
+			}
+
+			match collection.mint_permission {
+				MintPermission::Owner => false,
+				MintPermission::Minters => Minters::<T>::contains_key(collection_id, who),
+				MintPermission::Public => true,
+			}
+		}
+
+		/// `base` followed by `item_id` in decimal, e.g. `ipfs://<cid>/42`. `base` must be a
+		/// directory ending with `/`. Templated URIs have no content hash.
+		fn templated_uri(
+			base: &MetadataLocationOf<T>,
+			item_id: T::ItemId,
+		) -> Result<TokenUriOf<T>, DispatchError> {
+			let mut id: u128 = item_id.saturated_into();
+			let mut digits = Vec::new();
+			loop {
+				digits.push(b'0' + (id % 10) as u8);
+				id /= 10;
+				if id == 0 {
+					break
+				}
+			}
+
+			digits.reverse();
+			ensure!(base.is_directory(), Error::<T>::InvalidTokenUri);
+			let location = base.with_suffix(&digits).ok_or(Error::<T>::TokenUriTooLong)?;
+			Ok(TokenUri { location, content_hash: None })
+		}
 
-			Items::<T>::set(next_id, nft.into());
+		/// Whether `who` may transfer `item` without being its owner
+		fn is_delegate(
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			item: &ItemDetails<T::AccountId, T::TokenURILimit>,
+			who: &T::AccountId,
+		) -> bool {
+			Approvals::<T>::get(collection_id, item_id).as_ref() == Some(who) ||
+				Operators::<T>::contains_key(&item.owner, who)
+		}
+
+		/// Equipped items only move with the item they are equipped into. Otherwise an item, and
+		/// every item equipped into it, must be movable.
+		pub(crate) fn ensure_transferable(
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			item: &ItemDetails<T::AccountId, T::TokenURILimit>,
+		) -> DispatchResult {
+			ensure!(!Parents::<T>::contains_key((collection_id, item_id)), Error::<T>::Equipped);
+			Self::ensure_movable(collection_id, item_id, item)
+		}
+
+		/// Locked items can't be moved, and soulbound items only while the collection owner holds
+		/// them, i.e. to hand them out
+		fn ensure_movable(
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			item: &ItemDetails<T::AccountId, T::TokenURILimit>,
+		) -> DispatchResult {
+			ensure!(!Self::is_locked(&collection_id, &item_id), Error::<T>::Locked);
+
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			if item.soulbound || collection.soulbound {
+				ensure!(item.owner == collection.owner, Error::<T>::Soulbound);
+			}
+
+			for (child_collection_id, child_item_id) in
+				Children::<T>::iter_key_prefix((collection_id, item_id))
+			{
+				let child = Items::<T>::get(child_collection_id, child_item_id)
+					.ok_or(Error::<T>::ItemNotFound)?;
+				Self::ensure_movable(child_collection_id, child_item_id, &child)?;
+			}
+
+			Ok(())
+		}
+
+		/// Move an item, and every item equipped into it, to `to`. The items' approvals don't
+		/// carry over to the new owner.
+		pub(crate) fn do_transfer(
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			item: ItemDetails<T::AccountId, T::TokenURILimit>,
+			to: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_transferable(collection_id, item_id, &item)?;
+			Self::move_item(collection_id, item_id, item, to);
+
+			Ok(())
+		}
+
+		fn move_item(
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			mut item: ItemDetails<T::AccountId, T::TokenURILimit>,
+			to: T::AccountId,
+		) {
+			let from = item.owner.clone();
+			item.owner = to.clone();
+			Items::<T>::insert(collection_id, item_id, item);
+			Approvals::<T>::remove(collection_id, item_id);
+			AccountItems::<T>::remove(&from, (collection_id, item_id));
+			AccountItems::<T>::insert(&to, (collection_id, item_id), ());
+
+			let children: Vec<_> =
+				Children::<T>::iter_key_prefix((collection_id, item_id)).collect();
+			for (child_collection_id, child_item_id) in children {
+				if let Some(child) = Items::<T>::get(child_collection_id, child_item_id) {
+					Self::move_item(child_collection_id, child_item_id, child, to.clone());
+				}
+			}
+
+			Self::deposit_event(Event::<T>::Transferred(collection_id, item_id, from, to));
+		}
+
+		/// Number of items an item is nested in
+		fn nesting_depth(collection_id: T::CollectionId, item_id: T::ItemId) -> u32 {
+			let mut depth = 0;
+			let mut current = (collection_id, item_id);
+			while let Some(parent) = Parents::<T>::get(current) {
+				depth += 1;
+				current = parent;
+			}
+			depth
+		}
+
+		/// Number of levels of items nested in an item
+		fn nesting_height(collection_id: T::CollectionId, item_id: T::ItemId) -> u32 {
+			Children::<T>::iter_key_prefix((collection_id, item_id))
+				.map(|(child_collection_id, child_item_id)| {
+					Self::nesting_height(child_collection_id, child_item_id) + 1
+				})
+				.max()
+				.unwrap_or(0)
+		}
+
+		/// Whether `ancestor` is an item, or an item that it is nested in
+		fn is_nested_in(
+			item: (T::CollectionId, T::ItemId),
+			ancestor: (T::CollectionId, T::ItemId),
+		) -> bool {
+			let mut current = Some(item);
+			while let Some(item) = current {
+				if item == ancestor {
+					return true
+				}
+				current = Parents::<T>::get(item);
+			}
+			false
+		}
+
+		/// Up to `limit` of the items `who` owns, skipping the first `start`. Pages hold at most
+		/// `MAX_ITEMS_PAGE` items.
+		pub fn items_of(
+			who: &T::AccountId,
+			start: u32,
+			limit: u32,
+		) -> Vec<(T::CollectionId, T::ItemId)> {
+			AccountItems::<T>::iter_key_prefix(who)
+				.skip(start as usize)
+				.take(limit.min(MAX_ITEMS_PAGE) as usize)
+				.collect()
+		}
+
+		/// The token URI of an item resolved to a URI clients can fetch, e.g. `ipfs://<cid>/1.json`
+		/// or `onchain://0x<hash>` for a blob stored on chain
+		pub fn token_uri(collection_id: T::CollectionId, item_id: T::ItemId) -> Option<Vec<u8>> {
+			let item = Items::<T>::get(collection_id, item_id)?;
+			item.token_uri.map(|token_uri| token_uri.location.to_uri())
+		}
+
+		/// The Blake2-256 hash of an item's metadata JSON, if its token URI has one
+		pub fn token_content_hash(
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+		) -> Option<[u8; 32]> {
+			Items::<T>::get(collection_id, item_id)?.token_uri?.content_hash
+		}
+
+		/// The account using an item other than its owner, until its use expires
+		pub fn user_of(collection_id: T::CollectionId, item_id: T::ItemId) -> Option<T::AccountId> {
+			let (user, expires_at) = Users::<T>::get((collection_id, item_id))?;
+			if expires_at <= frame_system::Pallet::<T>::block_number() {
+				return None
+			}
+			Some(user)
+		}
+
+		/// Let `user` use an item until `expires_at`, unless another user's use is still active.
+		/// Doesn't check permissions.
+		pub(crate) fn do_set_user(
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			user: T::AccountId,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(expires_at > now, Error::<T>::InvalidExpiry);
+			ensure!(Self::user_of(collection_id, item_id).is_none(), Error::<T>::UserActive);
+
+			if let Some((_, expired_at)) = Users::<T>::get((collection_id, item_id)) {
+				UserExpiries::<T>::remove(expired_at, (collection_id, item_id));
+			}
+			Users::<T>::insert((collection_id, item_id), (user.clone(), expires_at));
+			UserExpiries::<T>::insert(expires_at, (collection_id, item_id), ());
+			if ExpiryCursor::<T>::get().is_none() {
+				ExpiryCursor::<T>::put(now);
+			}
+
+			Self::deposit_event(Event::<T>::UserSet(collection_id, item_id, user, expires_at));
+
+			Ok(())
+		}
+
+		/// Remove an item's user if its use expired at `block`
+		fn expire_user(block: T::BlockNumber, collection_id: T::CollectionId, item_id: T::ItemId) {
+			UserExpiries::<T>::remove(block, (collection_id, item_id));
+			if let Some((user, expires_at)) = Users::<T>::get((collection_id, item_id)) {
+				if expires_at == block {
+					Users::<T>::remove((collection_id, item_id));
+					Self::deposit_event(Event::<T>::UserExpired(collection_id, item_id, user));
+				}
+			}
+		}
+
+		/// Metadata managers may change any attribute, and game authorities the keys they were
+		/// granted. Returns whether `who` changes it as a game authority.
+		fn ensure_attribute_manager(
+			collection_id: T::CollectionId,
+			key: &AttributeKey<T>,
+			who: &T::AccountId,
+		) -> Result<bool, DispatchError> {
+			if Self::ensure_metadata_manager(collection_id, who).is_ok() {
+				return Ok(false)
+			}
+
+			ensure!(
+				GameAuthorities::<T>::contains_key((collection_id, who, key)),
+				Error::<T>::NoMetadataPermission,
+			);
+			Ok(true)
+		}
+
+		/// The collection owner and its game accounts may change the metadata of its items
+		fn ensure_metadata_manager(
+			collection_id: T::CollectionId,
+			who: &T::AccountId,
+		) -> DispatchResult {
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			ensure!(
+				collection.owner == *who || GameAccounts::<T>::contains_key(collection_id, who),
+				Error::<T>::NoMetadataPermission,
+			);
+			Ok(())
+		}
+
+		fn ensure_collection_owner(
+			collection_id: T::CollectionId,
+			who: &T::AccountId,
+		) -> DispatchResult {
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			ensure!(collection.owner == *who, Error::<T>::NotCollectionOwner);
+			Ok(())
+		}
+
+		/// What collection owners sign for a voucher: the genesis hash followed by the voucher,
+		/// so it can't be redeemed on another chain
+		pub fn voucher_payload(voucher: &MintVoucherOf<T>) -> Vec<u8> {
+			let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+			(genesis_hash, voucher).encode()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::weight(5_000_000)]
+		pub fn create_collection(
+			origin: OriginFor<T>,
+			metadata: BoundedVec<u8, T::CollectionMetadataLimit>,
+			max_supply: Option<u32>,
+			mint_permission: MintPermission,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let collection_id = NextCollectionId::<T>::get();
+
+			Self::do_create_collection(
+				collection_id,
+				owner,
+				metadata,
+				max_supply,
+				mint_permission,
+				true,
+			)
+		}
+
+		#[pallet::weight(1_000_000)]
+		pub fn set_mint_permission(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			mint_permission: MintPermission,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_collection_owner(collection_id, &sender)?;
+
+			Collections::<T>::mutate(collection_id, |collection| {
+				if let Some(collection) = collection {
+					collection.mint_permission = mint_permission;
+				}
+			});
+
+			Self::deposit_event(Event::<T>::MintPermissionSet(collection_id, mint_permission));
+
+			Ok(())
+		}
+
+		#[pallet::weight(1_000_000)]
+		pub fn set_minter(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			who: T::AccountId,
+			is_minter: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_collection_owner(collection_id, &sender)?;
+
+			if is_minter {
+				Minters::<T>::insert(collection_id, &who, ());
+			} else {
+				Minters::<T>::remove(collection_id, &who);
+			}
+
+			Self::deposit_event(Event::<T>::MinterSet(collection_id, who, is_minter));
+
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::mint())]
+		pub fn mint(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			token_uri: TokenUriOf<T>,
+			royalty: Option<u16>,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			ensure!(
+				Self::can_mint(collection_id, &collection, &owner),
+				Error::<T>::NoMintPermission,
+			);
+			let item_id = NextItemId::<T>::get();
+
+			Self::do_mint(
+				collection_id,
+				item_id,
+				owner.clone(),
+				owner.clone(),
+				Some(token_uri),
+				royalty,
+				Some(owner),
+			)
+		}
+
+		/// Mint an item to each of `recipients`, e.g. to launch a game drop. Items get sequential
+		/// IDs, and the token URIs are either given per recipient or a base URI followed by the
+		/// item ID.
+		#[pallet::weight(T::WeightInfo::mint_batch(recipients.len() as u32))]
+		pub fn mint_batch(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			recipients: Vec<T::AccountId>,
+			token_uris: BatchTokenUris<TokenUriOf<T>, MetadataLocationOf<T>>,
+			royalty: Option<u16>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!recipients.is_empty(), Error::<T>::EmptyBatch);
+			ensure!(recipients.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+			if let BatchTokenUris::Explicit(uris) = &token_uris {
+				ensure!(uris.len() == recipients.len(), Error::<T>::TokenUriCountMismatch);
+			}
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			ensure!(
+				Self::can_mint(collection_id, &collection, &sender),
+				Error::<T>::NoMintPermission,
+			);
+
+			let first_item_id = NextItemId::<T>::get();
+			let count = recipients.len() as u32;
+			for (index, owner) in recipients.into_iter().enumerate() {
+				let item_id = NextItemId::<T>::get();
+				let token_uri = match &token_uris {
+					BatchTokenUris::Explicit(uris) => uris[index].clone(),
+					BatchTokenUris::Sequential(base) => Self::templated_uri(base, item_id)?,
+				};
+				Self::do_mint(
+					collection_id,
+					item_id,
+					owner,
+					sender.clone(),
+					Some(token_uri),
+					royalty,
+					Some(sender.clone()),
+				)?;
+			}
+
+			Self::deposit_event(Event::<T>::BatchMinted(collection_id, first_item_id, count));
+
+			Ok(())
+		}
+
+		#[pallet::weight(1_000_000)]
+		pub fn set_game_account(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			who: T::AccountId,
+			is_game_account: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_collection_owner(collection_id, &sender)?;
+
+			if is_game_account {
+				GameAccounts::<T>::insert(collection_id, &who, ());
+			} else {
+				GameAccounts::<T>::remove(collection_id, &who);
+			}
+
+			Self::deposit_event(Event::<T>::GameAccountSet(collection_id, who, is_game_account));
+
+			Ok(())
+		}
+
+		/// Let `who`, e.g. a game server, change the attribute `key` of every item of a collection,
+		/// e.g. XP or level, or revoke it. Callable by the collection owner.
+		#[pallet::weight(1_000_000)]
+		pub fn set_game_authority(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			who: T::AccountId,
+			key: AttributeKey<T>,
+			is_authority: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_collection_owner(collection_id, &sender)?;
+
+			if is_authority {
+				GameAuthorities::<T>::insert((collection_id, &who, &key), ());
+			} else {
+				GameAuthorities::<T>::remove((collection_id, &who, &key));
+			}
+
+			Self::deposit_event(Event::<T>::GameAuthoritySet(
+				collection_id,
+				who,
+				key,
+				is_authority,
+			));
+
+			Ok(())
+		}
+
+		/// Burn an item. Callable by the item owner or a game account of its collection.
+		#[pallet::weight(5_000_000)]
+		pub fn burn(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let item = Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(
+				item.owner == sender || GameAccounts::<T>::contains_key(collection_id, &sender),
+				Error::<T>::NoBurnPermission,
+			);
+
+			Self::do_burn(collection_id, item_id)
+		}
+
+		/// Destroy an empty collection. All of its items must be burned first.
+		#[pallet::weight(5_000_000)]
+		pub fn destroy_collection(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			ensure!(collection.owner == sender, Error::<T>::NotCollectionOwner);
+			ensure!(collection.items == 0, Error::<T>::CollectionNotEmpty);
+
+			Collections::<T>::remove(collection_id);
+			if let Some(deposit) = CollectionDeposits::<T>::take(collection_id) {
+				T::Currency::unreserve(&collection.owner, deposit);
+			}
+			DepositOverrides::<T>::remove(collection_id);
+			let _ = Minters::<T>::clear_prefix(collection_id, u32::MAX, None);
+			let _ = GameAccounts::<T>::clear_prefix(collection_id, u32::MAX, None);
+			let _ = GameAuthorities::<T>::clear_prefix((collection_id,), u32::MAX, None);
+			let _ = UsedVouchers::<T>::clear_prefix(collection_id, u32::MAX, None);
+
+			Self::deposit_event(Event::<T>::CollectionDestroyed(collection_id));
+
+			Ok(())
+		}
+
+		#[pallet::weight(1_000_000)]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			to: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let item = Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(item.owner == sender, Error::<T>::NotItemOwner);
+
+			Self::do_transfer(collection_id, item_id, item, to)
+		}
+
+		/// Transfer an item on its owner's behalf. Callable by the account approved for the item
+		/// or an operator of its owner.
+		#[pallet::weight(1_000_000)]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			to: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let item = Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(
+				item.owner == sender || Self::is_delegate(collection_id, item_id, &item, &sender),
+				Error::<T>::NotApproved,
+			);
+
+			Self::do_transfer(collection_id, item_id, item, to)
+		}
+
+		/// Approve `delegate` to transfer an item. Replaces the item's previous approval.
+		#[pallet::weight(1_000_000)]
+		pub fn approve(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			delegate: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let item = Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(item.owner == sender, Error::<T>::NotItemOwner);
+
+			Approvals::<T>::insert(collection_id, item_id, &delegate);
+
+			Self::deposit_event(Event::<T>::ApprovalSet(collection_id, item_id, sender, delegate));
+
+			Ok(())
+		}
+
+		#[pallet::weight(1_000_000)]
+		pub fn cancel_approval(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let item = Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(item.owner == sender, Error::<T>::NotItemOwner);
+			ensure!(
+				Approvals::<T>::contains_key(collection_id, item_id),
+				Error::<T>::ApprovalNotFound,
+			);
+
+			Approvals::<T>::remove(collection_id, item_id);
+
+			Self::deposit_event(Event::<T>::ApprovalCanceled(collection_id, item_id, sender));
+
+			Ok(())
+		}
+
+		/// Allow or disallow `operator` to transfer every item the caller owns
+		#[pallet::weight(1_000_000)]
+		pub fn set_operator(
+			origin: OriginFor<T>,
+			operator: T::AccountId,
+			is_operator: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			if is_operator {
+				Operators::<T>::insert(&sender, &operator, ());
+			} else {
+				Operators::<T>::remove(&sender, &operator);
+			}
+
+			Self::deposit_event(Event::<T>::OperatorSet(sender, operator, is_operator));
+
+			Ok(())
+		}
+
+		/// Set the royalty, in basis points, paid to the collection owner on sales of items that
+		/// were minted without their own royalty
+		#[pallet::weight(1_000_000)]
+		pub fn set_collection_royalty(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			basis_points: u16,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_collection_owner(collection_id, &sender)?;
+			ensure!(basis_points <= T::MaxRoyalty::get(), Error::<T>::RoyaltyTooHigh);
+
+			Collections::<T>::mutate(collection_id, |collection| {
+				if let Some(collection) = collection {
+					collection.royalty = basis_points;
+				}
+			});
+
+			Self::deposit_event(Event::<T>::CollectionRoyaltySet(collection_id, basis_points));
+
+			Ok(())
+		}
+
+		/// Set an attribute of an item. Callable by the collection owner, its game accounts, and
+		/// the game authorities of the key.
+		#[pallet::weight(1_000_000)]
+		pub fn set_attribute(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			key: AttributeKey<T>,
+			value: BoundedVec<u8, T::AttributeValueLimit>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let is_authority = Self::ensure_attribute_manager(collection_id, &key, &sender)?;
+
+			Self::do_set_attribute(
+				collection_id,
+				item_id,
+				key.clone(),
+				value.clone(),
+				Some(&sender),
+			)?;
+
+			if is_authority {
+				Self::deposit_event(Event::<T>::AttributeChangedByAuthority(
+					collection_id,
+					item_id,
+					key,
+					Some(value),
+					sender,
+				));
+			}
+
+			Ok(())
+		}
+
+		#[pallet::weight(1_000_000)]
+		pub fn clear_attribute(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			key: AttributeKey<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let is_authority = Self::ensure_attribute_manager(collection_id, &key, &sender)?;
+			let mut item =
+				Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(!item.attributes_frozen, Error::<T>::AttributesFrozen);
+			ensure!(
+				Attributes::<T>::contains_key((collection_id, item_id, &key)),
+				Error::<T>::AttributeNotFound,
+			);
+
+			Attributes::<T>::remove((collection_id, item_id, &key));
+			if let Some((depositor, deposit)) =
+				AttributeDeposits::<T>::take((collection_id, item_id, &key))
+			{
+				T::Currency::unreserve(&depositor, deposit);
+			}
+			item.attributes = item.attributes.saturating_sub(1);
+			Items::<T>::insert(collection_id, item_id, item);
+
+			Self::deposit_event(Event::<T>::AttributeCleared(collection_id, item_id, key.clone()));
+			if is_authority {
+				Self::deposit_event(Event::<T>::AttributeChangedByAuthority(
+					collection_id,
+					item_id,
+					key,
+					None,
+					sender,
+				));
+			}
+
+			Ok(())
+		}
+
+		/// Change the token URI of an item. Callable by the collection owner and its game
+		/// accounts. The caller takes over the item's deposit, which is returned to its previous
+		/// holder.
+		#[pallet::weight(1_000_000)]
+		pub fn set_token_uri(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			token_uri: TokenUriOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_metadata_manager(collection_id, &sender)?;
+			ensure!(token_uri.location.is_valid(), Error::<T>::InvalidTokenUri);
+			let mut item =
+				Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(!item.uri_frozen, Error::<T>::TokenUriFrozen);
+
+			let old_deposit = ItemDeposits::<T>::take((collection_id, item_id));
+			let token_uri = Some(token_uri);
+			let deposit = Self::item_deposit(collection_id, token_uri.encoded_size());
+			if let Some(deposit) = Self::redeposit(old_deposit, Some(&sender), deposit)? {
+				ItemDeposits::<T>::insert((collection_id, item_id), deposit);
+			}
+			item.token_uri = token_uri;
+			Items::<T>::insert(collection_id, item_id, item);
+
+			Self::deposit_event(Event::<T>::TokenUriSet(collection_id, item_id));
+
+			Ok(())
+		}
+
+		/// Make the token URI and/or the attributes of an item immutable. Freezing can't be
+		/// undone.
+		#[pallet::weight(1_000_000)]
+		pub fn freeze_item(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			freeze_uri: bool,
+			freeze_attributes: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_metadata_manager(collection_id, &sender)?;
+			let mut item =
+				Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+
+			item.uri_frozen |= freeze_uri;
+			item.attributes_frozen |= freeze_attributes;
+			let (uri_frozen, attributes_frozen) = (item.uri_frozen, item.attributes_frozen);
+			Items::<T>::insert(collection_id, item_id, item);
+
+			Self::deposit_event(Event::<T>::ItemFrozen(
+				collection_id,
+				item_id,
+				uri_frozen,
+				attributes_frozen,
+			));
+
+			Ok(())
+		}
+
+		/// Let `user` use an item, e.g. rented game gear or land, until block `expires_at`. The
+		/// user stays when the item is transferred, and can't be replaced until its use expires.
+		/// Callable by the item owner or an account allowed to transfer the item.
+		#[pallet::weight(5_000_000)]
+		pub fn set_user(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			user: T::AccountId,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let item = Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(
+				item.owner == sender || Self::is_delegate(collection_id, item_id, &item, &sender),
+				Error::<T>::NotApproved,
+			);
+
+			Self::do_set_user(collection_id, item_id, user, expires_at)
+		}
+
+		/// Make an item, or with `item_id` `None` every item of the collection, soulbound. Soulbound
+		/// items can be handed out by the collection owner but never transferred after that.
+		/// This can't be undone.
+		#[pallet::weight(1_000_000)]
+		pub fn set_soulbound(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: Option<T::ItemId>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_collection_owner(collection_id, &sender)?;
+
+			match item_id {
+				Some(item_id) => Items::<T>::try_mutate(collection_id, item_id, |item| {
+					let item = item.as_mut().ok_or(Error::<T>::ItemNotFound)?;
+					item.soulbound = true;
+					Ok::<(), DispatchError>(())
+				})?,
+				None => Collections::<T>::mutate(collection_id, |collection| {
+					if let Some(collection) = collection {
+						collection.soulbound = true;
+					}
+				}),
+			}
+
+			Self::deposit_event(Event::<T>::SoulboundSet(collection_id, item_id));
+
+			Ok(())
+		}
+
+		/// Equip an item into another item the caller owns, e.g. a sword into a character. The
+		/// item then moves with the item it is equipped into, and can't be transferred on its
+		/// own until it is unequipped. Locked items can't be equipped.
+		#[pallet::weight(5_000_000)]
+		pub fn equip(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			parent_collection_id: T::CollectionId,
+			parent_item_id: T::ItemId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let item = Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			let parent = Items::<T>::get(parent_collection_id, parent_item_id)
+				.ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(item.owner == sender && parent.owner == sender, Error::<T>::NotItemOwner);
+			ensure!(!Self::is_locked(&collection_id, &item_id), Error::<T>::Locked);
+
+			let child_key = (collection_id, item_id);
+			let parent_key = (parent_collection_id, parent_item_id);
+			ensure!(!Parents::<T>::contains_key(child_key), Error::<T>::Equipped);
+			ensure!(!Self::is_nested_in(parent_key, child_key), Error::<T>::NestingCycle);
+			let depth = Self::nesting_depth(parent_collection_id, parent_item_id) +
+				1 + Self::nesting_height(collection_id, item_id);
+			ensure!(depth <= T::MaxNestingDepth::get(), Error::<T>::NestingTooDeep);
+			let children = Children::<T>::iter_key_prefix(parent_key).count() as u32;
+			ensure!(children < T::MaxChildren::get(), Error::<T>::TooManyChildren);
+
+			Parents::<T>::insert(child_key, parent_key);
+			Children::<T>::insert(parent_key, child_key, ());
+			Approvals::<T>::remove(collection_id, item_id);
+
+			Self::deposit_event(Event::<T>::ItemEquipped(
+				collection_id,
+				item_id,
+				parent_collection_id,
+				parent_item_id,
+			));
+
+			Ok(())
+		}
+
+		/// Take an equipped item out of the item it is equipped into. It stays with the same
+		/// owner.
+		#[pallet::weight(1_000_000)]
+		pub fn unequip(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let item = Items::<T>::get(collection_id, item_id).ok_or(Error::<T>::ItemNotFound)?;
+			ensure!(item.owner == sender, Error::<T>::NotItemOwner);
+
+			let child_key = (collection_id, item_id);
+			let (parent_collection_id, parent_item_id) =
+				Parents::<T>::take(child_key).ok_or(Error::<T>::NotEquipped)?;
+			Children::<T>::remove((parent_collection_id, parent_item_id), child_key);
+
+			Self::deposit_event(Event::<T>::ItemUnequipped(
+				collection_id,
+				item_id,
+				parent_collection_id,
+				parent_item_id,
+			));
+
+			Ok(())
+		}
+
+		/// Mint an item from a voucher the collection owner signed off-chain over its
+		/// `voucher_payload`, paying the voucher's price to the collection owner. The collection
+		/// owner is the item's creator. Each nonce can only be redeemed once per collection, up to
+		/// the voucher's expiry.
+		#[pallet::weight(10_000_000)]
+		pub fn redeem_voucher(
+			origin: OriginFor<T>,
+			voucher: MintVoucherOf<T>,
+			signature: T::OffchainSignature,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let collection_id = voucher.collection_id;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			ensure!(
+				signature.verify(&Self::voucher_payload(&voucher)[..], &collection.owner),
+				Error::<T>::InvalidSignature,
+			);
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= voucher.expires_at,
+				Error::<T>::VoucherExpired,
+			);
+			ensure!(
+				!UsedVouchers::<T>::contains_key(collection_id, voucher.nonce),
+				Error::<T>::VoucherUsed,
+			);
+
+			UsedVouchers::<T>::insert(collection_id, voucher.nonce, ());
+			<T::Assets as fungibles::Transfer<_>>::transfer(
+				voucher.asset_id,
+				&sender,
+				&collection.owner,
+				voucher.price,
+				false,
+			)?;
+			let item_id = NextItemId::<T>::get();
+			Self::do_mint(
+				collection_id,
+				item_id,
+				sender.clone(),
+				collection.owner,
+				Some(voucher.token_uri),
+				voucher.royalty,
+				Some(sender.clone()),
+			)?;
+
+			Self::deposit_event(Event::<T>::VoucherRedeemed(
+				collection_id,
+				item_id,
+				sender,
+				voucher.nonce,
+			));
+
+			Ok(())
+		}
+
+		/// Revoke a signed voucher of a collection the caller owns, so its nonce can't be
+		/// redeemed
+		#[pallet::weight(1_000_000)]
+		pub fn cancel_voucher(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			nonce: u64,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_collection_owner(collection_id, &sender)?;
+			ensure!(
+				!UsedVouchers::<T>::contains_key(collection_id, nonce),
+				Error::<T>::VoucherUsed,
+			);
+
+			UsedVouchers::<T>::insert(collection_id, nonce, ());
+
+			Self::deposit_event(Event::<T>::VoucherCanceled(collection_id, nonce));
+
+			Ok(())
+		}
+
+		/// Set the item deposit and deposit per byte of a collection, e.g. to lower them for a
+		/// verified collection, or `None` to go back to the defaults. Deposits already reserved
+		/// are returned in full later.
+		#[pallet::weight(1_000_000)]
+		pub fn set_collection_deposits(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			deposits: Option<(DepositBalanceOf<T>, DepositBalanceOf<T>)>,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(Collections::<T>::contains_key(collection_id), Error::<T>::CollectionNotFound);
+
+			match deposits {
+				Some(deposits) => DepositOverrides::<T>::insert(collection_id, deposits),
+				None => DepositOverrides::<T>::remove(collection_id),
+			}
 
-			Self::deposit_event(Event::<T>::NftMinted(next_id, owner));
+			Self::deposit_event(Event::<T>::CollectionDepositsSet(collection_id, deposits));
 
 			Ok(())
 		}
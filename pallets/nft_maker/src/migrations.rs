@@ -0,0 +1,106 @@
+use crate::{
+	token_uri::{MetadataLocation, TokenUri},
+	types::MintPermission,
+	Config, Pallet,
+};
+use frame_support::{
+	storage::migration::{remove_storage_prefix, storage_key_iter},
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+	weights::Weight,
+	Blake2_128Concat,
+};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+pub(crate) mod v0 {
+	use codec::{Decode, Encode};
+	use frame_support::{traits::Get, BoundedVec};
+
+	/// An item as stored before collections, keyed by its ID alone
+	#[derive(Encode, Decode)]
+	pub struct ItemDetails<AccountId, TokenURILimit: Get<u32>> {
+		pub owner: AccountId,
+		pub token_uri: BoundedVec<u8, TokenURILimit>,
+	}
+}
+
+/// Moves the items of the original layout into the current one.
+///
+/// Before v1 items had no collection, were keyed by their ID alone and stored their token URI as
+/// raw bytes, with the last used ID kept in a `Counter` map. Every item is minted again with the
+/// same ID into the collection given by `LegacyCollection`, which is created for them and owned by
+/// its account. Owners become the creators, no deposits are reserved, and URIs that don't parse as
+/// a metadata location are dropped and counted in the log, so they can be set again with
+/// `set_token_uri`.
+pub struct MigrateToV1<T, LegacyCollection>(PhantomData<(T, LegacyCollection)>);
+
+impl<T, LegacyCollection> OnRuntimeUpgrade for MigrateToV1<T, LegacyCollection>
+where
+	T: Config,
+	LegacyCollection: Get<(T::CollectionId, T::AccountId)>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 0 {
+			log::info!(target: "nft_maker", "Items already migrated, skipping");
+			return T::DbWeight::get().reads(1)
+		}
+
+		let pallet = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+		let items: Vec<_> = storage_key_iter::<
+			T::ItemId,
+			v0::ItemDetails<T::AccountId, T::TokenURILimit>,
+			Blake2_128Concat,
+		>(pallet, b"Items")
+		.drain()
+		.collect();
+		remove_storage_prefix(pallet, b"Counter", &[]);
+		remove_storage_prefix(pallet, b"CounterForCounter", &[]);
+
+		let count = items.len() as u64;
+		let (collection_id, collection_owner) = LegacyCollection::get();
+		if !items.is_empty() {
+			let created = Pallet::<T>::do_create_collection(
+				collection_id,
+				collection_owner,
+				Default::default(),
+				None,
+				MintPermission::Owner,
+				false,
+			);
+			if let Err(error) = created {
+				log::error!(target: "nft_maker", "Can't create the legacy collection: {:?}", error);
+			}
+		}
+
+		let mut unparseable = 0u64;
+		let mut failed = 0u64;
+		for (item_id, item) in items {
+			let location = MetadataLocation::from_uri(&item.token_uri);
+			if location.is_none() && !item.token_uri.is_empty() {
+				unparseable += 1;
+			}
+			let token_uri = location.map(|location| TokenUri { location, content_hash: None });
+			let minted = Pallet::<T>::do_mint(
+				collection_id,
+				item_id,
+				item.owner.clone(),
+				item.owner,
+				token_uri,
+				None,
+				None,
+			);
+			if minted.is_err() {
+				failed += 1;
+			}
+		}
+		log::info!(
+			target: "nft_maker",
+			"Migrated {} items, dropped {} unparseable token URIs, failed to migrate {}",
+			count - failed,
+			unparseable,
+			failed
+		);
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count * 4 + 3, count * 5 + 5)
+	}
+}
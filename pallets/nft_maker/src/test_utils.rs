@@ -0,0 +1,172 @@
+//! A mock runtime for testing pallets built on the NFT Maker, behind the `test-utils` feature.
+
+/// Declare a `Test` runtime with System, Balances, Assets and the NFT Maker as `NftMaker`,
+/// followed by the given pallets, whose `Config` the caller implements. `OnBurn = <type>;`
+/// sets the NFT Maker's burn hook, which defaults to `()`.
+///
+/// Also declares ALICE, BOB and CHARLIE, funded with 1_000_000 each, and the `create_collection`,
+/// `create_collection_with_max_supply`, `mint`, `last_event` and `new_test_ext` helpers.
+#[macro_export]
+macro_rules! nft_maker_mock {
+	(OnBurn = $on_burn:ty; $($pallets:tt)*) => {
+		type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+		type Block = frame_system::mocking::MockBlock<Test>;
+
+		// Configure a mock runtime to test the pallet.
+		frame_support::construct_runtime!(
+			pub enum Test where
+				Block = Block,
+				NodeBlock = Block,
+				UncheckedExtrinsic = UncheckedExtrinsic,
+			{
+				System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+				Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+				Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+				NftMaker: pallet_nft_maker::{Pallet, Call, Storage, Event<T>},
+				$($pallets)*
+			}
+		);
+
+		impl frame_system::Config for Test {
+			type BaseCallFilter = frame_support::traits::Everything;
+			type BlockWeights = ();
+			type BlockLength = ();
+			type DbWeight = ();
+			type Origin = Origin;
+			type Call = Call;
+			type Index = u64;
+			type BlockNumber = u64;
+			type Hash = sp_core::H256;
+			type Hashing = sp_runtime::traits::BlakeTwo256;
+			type AccountId = u64;
+			type Lookup = sp_runtime::traits::IdentityLookup<Self::AccountId>;
+			type Header = sp_runtime::testing::Header;
+			type Event = Event;
+			type BlockHashCount = frame_support::traits::ConstU64<250>;
+			type Version = ();
+			type PalletInfo = PalletInfo;
+			type AccountData = pallet_balances::AccountData<u64>;
+			type OnNewAccount = ();
+			type OnKilledAccount = ();
+			type SystemWeightInfo = ();
+			type SS58Prefix = frame_support::traits::ConstU16<42>;
+			type OnSetCode = ();
+			type MaxConsumers = frame_support::traits::ConstU32<16>;
+		}
+
+		impl pallet_balances::Config for Test {
+			type MaxLocks = ();
+			type MaxReserves = ();
+			type ReserveIdentifier = [u8; 8];
+			type Balance = u64;
+			type Event = Event;
+			type DustRemoval = ();
+			type ExistentialDeposit = frame_support::traits::ConstU64<1>;
+			type AccountStore = System;
+			type WeightInfo = ();
+		}
+
+		impl pallet_assets::Config for Test {
+			type Event = Event;
+			type Balance = u64;
+			type AssetId = u32;
+			type Currency = Balances;
+			type ForceOrigin = frame_system::EnsureRoot<u64>;
+			type AssetDeposit = frame_support::traits::ConstU64<1>;
+			type AssetAccountDeposit = frame_support::traits::ConstU64<1>;
+			type MetadataDepositBase = frame_support::traits::ConstU64<0>;
+			type MetadataDepositPerByte = frame_support::traits::ConstU64<0>;
+			type ApprovalDeposit = frame_support::traits::ConstU64<1>;
+			type StringLimit = frame_support::traits::ConstU32<50>;
+			type Freezer = ();
+			type Extra = ();
+			type WeightInfo = ();
+		}
+
+		impl pallet_nft_maker::Config for Test {
+			type Event = Event;
+			type ItemId = u32;
+			type CollectionId = u32;
+			type TokenURILimit = frame_support::traits::ConstU32<128>;
+			type CollectionMetadataLimit = frame_support::traits::ConstU32<64>;
+			type AttributeKeyLimit = frame_support::traits::ConstU32<16>;
+			type AttributeValueLimit = frame_support::traits::ConstU32<32>;
+			type MaxAttributes = frame_support::traits::ConstU32<4>;
+			type MaxRoyalty = frame_support::traits::ConstU16<2_500>;
+			type MaxNestingDepth = frame_support::traits::ConstU32<2>;
+			type MaxChildren = frame_support::traits::ConstU32<2>;
+			type MaxBatchSize = frame_support::traits::ConstU32<10>;
+			type Currency = Balances;
+			type ItemDeposit = frame_support::traits::ConstU64<10>;
+			type DepositPerByte = frame_support::traits::ConstU64<1>;
+			type ForceOrigin = frame_system::EnsureRoot<u64>;
+			type Assets = Assets;
+			type OffchainSignature = sp_runtime::testing::TestSignature;
+			type OffchainPublic = sp_runtime::testing::UintAuthorityId;
+			type OnBurn = $on_burn;
+			type WeightInfo = ();
+		}
+
+		pub const ALICE: u64 = 1;
+		pub const BOB: u64 = 2;
+		pub const CHARLIE: u64 = 3;
+
+		/// Create a collection owned by `owner`
+		pub fn create_collection(owner: u64) -> u32 {
+			create_collection_with_max_supply(owner, None)
+		}
+
+		/// Create a collection owned by `owner` with an optional max supply
+		pub fn create_collection_with_max_supply(owner: u64, max_supply: Option<u32>) -> u32 {
+			NftMaker::create_collection(
+				Origin::signed(owner),
+				Default::default(),
+				max_supply,
+				pallet_nft_maker::MintPermission::Owner,
+			)
+			.unwrap();
+			match last_event() {
+				Event::NftMaker(pallet_nft_maker::Event::CollectionCreated(collection_id, _)) =>
+					collection_id,
+				event => panic!("Unexpected event {:?}", event),
+			}
+		}
+
+		/// Mint an item of a collection `owner` owns to them
+		pub fn mint(owner: u64, collection_id: u32) -> u32 {
+			let token_uri = pallet_nft_maker::TokenUri {
+				location: pallet_nft_maker::MetadataLocation::OnChain([0; 32]),
+				content_hash: None,
+			};
+			NftMaker::mint(Origin::signed(owner), collection_id, token_uri, None).unwrap();
+			match last_event() {
+				Event::NftMaker(pallet_nft_maker::Event::NftMinted(_, item_id, _)) => item_id,
+				event => panic!("Unexpected event {:?}", event),
+			}
+		}
+
+		pub fn last_event() -> Event {
+			System::events().pop().expect("An event was deposited").event
+		}
+
+		// Build genesis storage according to the mock runtime.
+		pub fn new_test_ext() -> sp_io::TestExternalities {
+			use frame_support::traits::GenesisBuild;
+
+			let mut storage =
+				frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+			pallet_balances::GenesisConfig::<Test> {
+				balances: vec![(ALICE, 1_000_000), (BOB, 1_000_000), (CHARLIE, 1_000_000)],
+			}
+			.assimilate_storage(&mut storage)
+			.unwrap();
+
+			let mut ext: sp_io::TestExternalities = storage.into();
+			ext.execute_with(|| System::set_block_number(1));
+			ext
+		}
+	};
+	($($pallets:tt)*) => {
+		$crate::nft_maker_mock!(OnBurn = (); $($pallets)*);
+	};
+}
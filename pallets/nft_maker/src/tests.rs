@@ -1,20 +1,1021 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	migrations::{v0, MigrateToV1},
+	mock::*,
+	AccountItems, Approvals, AttributeKey, Attributes, BatchTokenUris, Children, Collections,
+	Error, GenesisConfig, Items, MetadataLocation, MintPermission, MintVoucherOf, NextCollectionId,
+	NextItemId, Pallet, Parents, TokenUri, UsedVouchers,
+};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok, parameter_types,
+	storage::migration::{have_storage_value, put_storage_value},
+	traits::{
+		tokens::nonfungibles::{Create, Inspect, Mutate, Transfer},
+		ConstU32, GenesisBuild, GetStorageVersion, OnRuntimeUpgrade, StorageVersion,
+	},
+	Blake2_128Concat, BoundedVec, StorageHasher, Twox128,
+};
+use pallet_custom_traits::{Lockable, Mint, Ownership, Royalty};
+use sp_runtime::{testing::TestSignature, Permill};
+
+const CID_V1: &[u8] = b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+const LEGACY_OWNER: u64 = 99;
+
+parameter_types! {
+	pub const LegacyCollection: (u32, u64) = (0, LEGACY_OWNER);
+}
+
+fn put_baseline_item(item_id: u32, owner: u64, token_uri: &[u8]) {
+	let item = v0::ItemDetails::<u64, ConstU32<128>> {
+		owner,
+		token_uri: token_uri.to_vec().try_into().unwrap(),
+	};
+	let key = Blake2_128Concat::hash(&item_id.encode());
+	put_storage_value(b"NftMaker", b"Items", &key, item);
+}
+
+#[test]
+fn migration_moves_baseline_items_into_the_legacy_collection() {
+	new_test_ext().execute_with(|| {
+		let uri = [&b"ipfs://"[..], CID_V1, b"/1.json"].concat();
+		put_baseline_item(1, ALICE, &uri);
+		put_baseline_item(2, BOB, b"not a uri");
+		let counter_key = Twox128::hash(&LEGACY_OWNER.encode());
+		put_storage_value(b"NftMaker", b"Counter", &counter_key, 2u32);
+		put_storage_value(b"NftMaker", b"CounterForCounter", &[], 1u32);
+
+		MigrateToV1::<Test, LegacyCollection>::on_runtime_upgrade();
+
+		let item = Items::<Test>::get(0, 1).unwrap();
+		assert_eq!(item.owner, ALICE);
+		assert_eq!(item.creator, ALICE);
+		assert_eq!(
+			item.token_uri,
+			Some(TokenUri {
+				location: MetadataLocation::from_uri(&uri).unwrap(),
+				content_hash: None
+			})
+		);
+		let item = Items::<Test>::get(0, 2).unwrap();
+		assert_eq!(item.owner, BOB);
+		assert_eq!(item.token_uri, None);
+		assert!(AccountItems::<Test>::contains_key(ALICE, (0, 1)));
+		assert!(AccountItems::<Test>::contains_key(BOB, (0, 2)));
+
+		let collection = Collections::<Test>::get(0).unwrap();
+		assert_eq!(collection.owner, LEGACY_OWNER);
+		assert_eq!(collection.items, 2);
+		assert_eq!(NextItemId::<Test>::get(), 3);
+		assert!(!have_storage_value(b"NftMaker", b"Counter", &counter_key));
+		assert!(!have_storage_value(b"NftMaker", b"CounterForCounter", &[]));
+		assert_eq!(NftMaker::on_chain_storage_version(), StorageVersion::new(1));
+	});
+}
+
+#[test]
+fn migration_runs_once() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<NftMaker>();
+		put_baseline_item(1, ALICE, b"");
+
+		MigrateToV1::<Test, LegacyCollection>::on_runtime_upgrade();
+
+		assert!(Collections::<Test>::get(0).is_none());
+	});
+}
+
+#[test]
+fn equip_and_unequip() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let character = mint(ALICE, collection_id);
+		let sword = mint(ALICE, collection_id);
+
+		assert_ok!(NftMaker::equip(
+			Origin::signed(ALICE),
+			collection_id,
+			sword,
+			collection_id,
+			character
+		));
+
+		assert_eq!(Parents::<Test>::get((collection_id, sword)), Some((collection_id, character)));
+		assert!(Children::<Test>::contains_key((collection_id, character), (collection_id, sword)));
+		assert_noop!(
+			NftMaker::transfer(Origin::signed(ALICE), collection_id, sword, BOB),
+			Error::<Test>::Equipped
+		);
+		assert_noop!(
+			NftMaker::burn(Origin::signed(ALICE), collection_id, sword),
+			Error::<Test>::Equipped
+		);
+
+		assert_ok!(NftMaker::unequip(Origin::signed(ALICE), collection_id, sword));
+
+		assert_eq!(Parents::<Test>::get((collection_id, sword)), None);
+		assert!(!Children::<Test>::contains_key(
+			(collection_id, character),
+			(collection_id, sword)
+		));
+		assert_noop!(
+			NftMaker::unequip(Origin::signed(ALICE), collection_id, sword),
+			Error::<Test>::NotEquipped
+		);
+	});
+}
+
+#[test]
+fn equip_requires_owning_both_items() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let character = mint(ALICE, collection_id);
+		let sword = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, sword, BOB));
+
+		assert_noop!(
+			NftMaker::equip(Origin::signed(ALICE), collection_id, sword, collection_id, character),
+			Error::<Test>::NotItemOwner
+		);
+		assert_noop!(
+			NftMaker::equip(Origin::signed(BOB), collection_id, sword, collection_id, character),
+			Error::<Test>::NotItemOwner
+		);
+	});
+}
+
+#[test]
+fn equip_rejects_locked_items() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let character = mint(ALICE, collection_id);
+		let sword = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::lock(&collection_id, &sword, *b"stakings"));
+
+		assert_noop!(
+			NftMaker::equip(Origin::signed(ALICE), collection_id, sword, collection_id, character),
+			Error::<Test>::Locked
+		);
+	});
+}
+
+#[test]
+fn equip_rejects_cycles() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let character = mint(ALICE, collection_id);
+		let bag = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::equip(
+			Origin::signed(ALICE),
+			collection_id,
+			bag,
+			collection_id,
+			character
+		));
+
+		assert_noop!(
+			NftMaker::equip(Origin::signed(ALICE), collection_id, character, collection_id, bag),
+			Error::<Test>::NestingCycle
+		);
+		assert_noop!(
+			NftMaker::equip(Origin::signed(ALICE), collection_id, bag, collection_id, bag),
+			Error::<Test>::Equipped
+		);
+	});
+}
+
+#[test]
+fn equip_limits_nesting() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let character = mint(ALICE, collection_id);
+		let bag = mint(ALICE, collection_id);
+		let sword = mint(ALICE, collection_id);
+		let gem = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::equip(
+			Origin::signed(ALICE),
+			collection_id,
+			bag,
+			collection_id,
+			character
+		));
+		assert_ok!(NftMaker::equip(
+			Origin::signed(ALICE),
+			collection_id,
+			sword,
+			collection_id,
+			bag
+		));
+
+		assert_noop!(
+			NftMaker::equip(Origin::signed(ALICE), collection_id, gem, collection_id, sword),
+			Error::<Test>::NestingTooDeep
+		);
+
+		let shield = mint(ALICE, collection_id);
+		let potion = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::equip(Origin::signed(ALICE), collection_id, gem, collection_id, bag));
+		assert_noop!(
+			NftMaker::equip(Origin::signed(ALICE), collection_id, shield, collection_id, bag),
+			Error::<Test>::TooManyChildren
+		);
+		assert_ok!(NftMaker::equip(
+			Origin::signed(ALICE),
+			collection_id,
+			shield,
+			collection_id,
+			potion
+		));
+		assert_noop!(
+			NftMaker::equip(Origin::signed(ALICE), collection_id, potion, collection_id, bag),
+			Error::<Test>::NestingTooDeep
+		);
+	});
+}
+
+#[test]
+fn transfer_moves_equipped_items() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let character = mint(ALICE, collection_id);
+		let bag = mint(ALICE, collection_id);
+		let sword = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::equip(
+			Origin::signed(ALICE),
+			collection_id,
+			bag,
+			collection_id,
+			character
+		));
+		assert_ok!(NftMaker::equip(
+			Origin::signed(ALICE),
+			collection_id,
+			sword,
+			collection_id,
+			bag
+		));
+
+		assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, character, BOB));
+
+		for item_id in [character, bag, sword] {
+			assert_eq!(Items::<Test>::get(collection_id, item_id).unwrap().owner, BOB);
+			assert!(AccountItems::<Test>::contains_key(BOB, (collection_id, item_id)));
+			assert!(!AccountItems::<Test>::contains_key(ALICE, (collection_id, item_id)));
+		}
+		assert_eq!(Parents::<Test>::get((collection_id, sword)), Some((collection_id, bag)));
+	});
+}
+
+#[test]
+fn equipped_items_are_owned_by_the_owner_of_their_parent() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let character = mint(ALICE, collection_id);
+		let sword = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::equip(
+			Origin::signed(ALICE),
+			collection_id,
+			sword,
+			collection_id,
+			character
+		));
+
+		assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, character, BOB));
+
+		assert!(NftMaker::is_owner(&collection_id, &sword, &BOB));
+		assert!(!NftMaker::is_owner(&collection_id, &sword, &ALICE));
+		assert_noop!(
+			NftMaker::unequip(Origin::signed(ALICE), collection_id, sword),
+			Error::<Test>::NotItemOwner
+		);
+		assert_ok!(NftMaker::unequip(Origin::signed(BOB), collection_id, sword));
+		assert!(NftMaker::is_owner(&collection_id, &sword, &BOB));
+	});
+}
+
+#[test]
+fn transfer_fails_if_an_equipped_item_is_locked() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let character = mint(ALICE, collection_id);
+		let sword = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::equip(
+			Origin::signed(ALICE),
+			collection_id,
+			sword,
+			collection_id,
+			character
+		));
+		assert_ok!(NftMaker::lock(&collection_id, &sword, *b"stakings"));
+
+		assert_noop!(
+			NftMaker::transfer(Origin::signed(ALICE), collection_id, character, BOB),
+			Error::<Test>::Locked
+		);
+	});
+}
+
+fn voucher(collection_id: u32, nonce: u64) -> MintVoucherOf<Test> {
+	assert_ok!(Assets::force_create(Origin::root(), 0, ALICE, true, 1));
+	assert_ok!(Assets::mint(Origin::signed(ALICE), 0, BOB, 1_000));
+	MintVoucherOf::<Test> {
+		collection_id,
+		token_uri: TokenUri { location: MetadataLocation::OnChain([1; 32]), content_hash: None },
+		royalty: None,
+		asset_id: 0,
+		price: 100,
+		nonce,
+		expires_at: 10,
+	}
+}
+
+fn sign(signer: u64, voucher: &MintVoucherOf<Test>) -> TestSignature {
+	TestSignature(signer, Pallet::<Test>::voucher_payload(voucher))
+}
+
+#[test]
+fn redeem_voucher_mints_and_pays_the_collection_owner() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let voucher = voucher(collection_id, 7);
+		let item_id = NextItemId::<Test>::get();
+
+		assert_ok!(NftMaker::redeem_voucher(
+			Origin::signed(BOB),
+			voucher.clone(),
+			sign(ALICE, &voucher)
+		));
+
+		let item = Items::<Test>::get(collection_id, item_id).unwrap();
+		assert_eq!(item.owner, BOB);
+		assert_eq!(item.creator, ALICE);
+		assert_eq!(Assets::balance(0, ALICE), 100);
+		assert_noop!(
+			NftMaker::redeem_voucher(Origin::signed(BOB), voucher.clone(), sign(ALICE, &voucher)),
+			Error::<Test>::VoucherUsed
+		);
+	});
+}
+
+#[test]
+fn redeem_voucher_requires_a_signature_for_this_chain() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let voucher = voucher(collection_id, 7);
+
+		assert_noop!(
+			NftMaker::redeem_voucher(Origin::signed(BOB), voucher.clone(), sign(BOB, &voucher)),
+			Error::<Test>::InvalidSignature
+		);
+		let without_genesis = TestSignature(ALICE, voucher.encode());
+		assert_noop!(
+			NftMaker::redeem_voucher(Origin::signed(BOB), voucher, without_genesis),
+			Error::<Test>::InvalidSignature
+		);
+	});
+}
 
 #[test]
-fn it_works_for_default_value() {
+fn redeem_voucher_fails_after_expiry() {
 	new_test_ext().execute_with(|| {
-		// Dispatch a signed extrinsic.
-		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
-		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
+		let collection_id = create_collection(ALICE);
+		let voucher = voucher(collection_id, 7);
+		System::set_block_number(11);
+
+		assert_noop!(
+			NftMaker::redeem_voucher(Origin::signed(BOB), voucher.clone(), sign(ALICE, &voucher)),
+			Error::<Test>::VoucherExpired
+		);
 	});
 }
 
 #[test]
-fn correct_error_for_none_value() {
+fn cancel_voucher() {
 	new_test_ext().execute_with(|| {
-		// Ensure the expected error is thrown when no value is present.
-		assert_noop!(TemplateModule::cause_error(Origin::signed(1)), Error::<Test>::NoneValue);
+		let collection_id = create_collection(ALICE);
+		let voucher = voucher(collection_id, 7);
+		assert_noop!(
+			NftMaker::cancel_voucher(Origin::signed(BOB), collection_id, 7),
+			Error::<Test>::NotCollectionOwner
+		);
+
+		assert_ok!(NftMaker::cancel_voucher(Origin::signed(ALICE), collection_id, 7));
+
+		assert_noop!(
+			NftMaker::redeem_voucher(Origin::signed(BOB), voucher.clone(), sign(ALICE, &voucher)),
+			Error::<Test>::VoucherUsed
+		);
+		assert_noop!(
+			NftMaker::cancel_voucher(Origin::signed(ALICE), collection_id, 7),
+			Error::<Test>::VoucherUsed
+		);
+	});
+}
+
+#[test]
+fn mint_batch_appends_item_ids_to_a_base_directory() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = NextItemId::<Test>::get();
+		let base = [&b"ipfs://"[..], CID_V1, b"/"].concat();
+		let token_uris = BatchTokenUris::Sequential(MetadataLocation::from_uri(&base).unwrap());
+
+		assert_ok!(NftMaker::mint_batch(
+			Origin::signed(ALICE),
+			collection_id,
+			vec![BOB, CHARLIE],
+			token_uris,
+			None
+		));
+
+		for (offset, owner) in [BOB, CHARLIE].into_iter().enumerate() {
+			let item_id = item_id + offset as u32;
+			let uri = [base.clone(), item_id.to_string().into_bytes()].concat();
+			assert_eq!(Items::<Test>::get(collection_id, item_id).unwrap().owner, owner);
+			assert_eq!(NftMaker::token_uri(collection_id, item_id), Some(uri));
+		}
+	});
+}
+
+#[test]
+fn token_content_hash_is_the_hash_minted_with() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let hashed = NextItemId::<Test>::get();
+		let token_uri =
+			TokenUri { location: MetadataLocation::OnChain([1; 32]), content_hash: Some([2; 32]) };
+		assert_ok!(NftMaker::mint(Origin::signed(ALICE), collection_id, token_uri, None));
+		let unhashed = mint(ALICE, collection_id);
+
+		assert_eq!(NftMaker::token_content_hash(collection_id, hashed), Some([2; 32]));
+		assert_eq!(NftMaker::token_content_hash(collection_id, unhashed), None);
+		assert_eq!(NftMaker::token_content_hash(collection_id, unhashed + 1), None);
+	});
+}
+
+#[test]
+fn mint_batch_requires_a_base_directory() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let base = MetadataLocation::from_uri(&[&b"ipfs://"[..], CID_V1].concat()).unwrap();
+
+		assert_noop!(
+			NftMaker::mint_batch(
+				Origin::signed(ALICE),
+				collection_id,
+				vec![BOB],
+				BatchTokenUris::Sequential(base),
+				None
+			),
+			Error::<Test>::InvalidTokenUri
+		);
+	});
+}
+
+#[test]
+fn burn_fails_while_an_item_is_rented() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::set_user(Origin::signed(ALICE), collection_id, item_id, BOB, 5));
+
+		assert_noop!(
+			NftMaker::burn(Origin::signed(ALICE), collection_id, item_id),
+			Error::<Test>::UserActive
+		);
+
+		System::set_block_number(5);
+		assert_ok!(NftMaker::burn(Origin::signed(ALICE), collection_id, item_id));
+		assert!(!Items::<Test>::contains_key(collection_id, item_id));
+	});
+}
+
+#[test]
+fn items_minted_by_other_pallets_reserve_a_deposit_from_the_recipient() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+
+		let item_id = <NftMaker as Mint<_, _, _>>::mint(&collection_id, &BOB).unwrap();
+
+		// `ItemDeposit` plus a byte for the missing token URI
+		assert_eq!(Balances::reserved_balance(BOB), 11);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+		assert_ok!(NftMaker::burn(Origin::signed(BOB), collection_id, item_id));
+		assert_eq!(Balances::reserved_balance(BOB), 0);
+	});
+}
+
+#[test]
+fn burn_is_limited_to_the_owner_and_game_accounts() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(ALICE, collection_id);
+		let burned_by_game = mint(ALICE, collection_id);
+
+		assert_noop!(
+			NftMaker::burn(Origin::signed(BOB), collection_id, item_id),
+			Error::<Test>::NoBurnPermission
+		);
+		assert_ok!(NftMaker::set_game_account(Origin::signed(ALICE), collection_id, BOB, true));
+		assert_ok!(NftMaker::burn(Origin::signed(BOB), collection_id, burned_by_game));
+		assert_ok!(NftMaker::burn(Origin::signed(ALICE), collection_id, item_id));
+
+		assert!(!Items::<Test>::contains_key(collection_id, item_id));
+		assert!(!AccountItems::<Test>::contains_key(ALICE, (collection_id, item_id)));
+		assert_eq!(Collections::<Test>::get(collection_id).unwrap().items, 0);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+	});
+}
+
+#[test]
+fn destroy_collection_requires_an_empty_collection_and_clears_it() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(NftMaker::create_collection(
+			Origin::signed(ALICE),
+			b"meta".to_vec().try_into().unwrap(),
+			None,
+			MintPermission::Owner
+		));
+		let collection_id = 0;
+		let item_id = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::cancel_voucher(Origin::signed(ALICE), collection_id, 1));
+
+		assert_noop!(
+			NftMaker::destroy_collection(Origin::signed(ALICE), collection_id),
+			Error::<Test>::CollectionNotEmpty
+		);
+		assert_ok!(NftMaker::burn(Origin::signed(ALICE), collection_id, item_id));
+		assert_noop!(
+			NftMaker::destroy_collection(Origin::signed(BOB), collection_id),
+			Error::<Test>::NotCollectionOwner
+		);
+		assert_ok!(NftMaker::destroy_collection(Origin::signed(ALICE), collection_id));
+
+		assert!(!Collections::<Test>::contains_key(collection_id));
+		assert!(!UsedVouchers::<Test>::contains_key(collection_id, 1));
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+	});
+}
+
+#[test]
+fn approved_accounts_and_operators_can_transfer_on_the_owners_behalf() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(ALICE, collection_id);
+
+		assert_ok!(NftMaker::approve(Origin::signed(ALICE), collection_id, item_id, BOB));
+		assert_ok!(NftMaker::transfer_from(Origin::signed(BOB), collection_id, item_id, CHARLIE));
+		assert_eq!(Items::<Test>::get(collection_id, item_id).unwrap().owner, CHARLIE);
+		assert!(!Approvals::<Test>::contains_key(collection_id, item_id));
+
+		assert_ok!(NftMaker::set_operator(Origin::signed(CHARLIE), ALICE, true));
+		assert_ok!(NftMaker::transfer_from(Origin::signed(ALICE), collection_id, item_id, BOB));
+		assert_ok!(NftMaker::transfer(Origin::signed(BOB), collection_id, item_id, ALICE));
+		assert_eq!(Items::<Test>::get(collection_id, item_id).unwrap().owner, ALICE);
+		assert!(AccountItems::<Test>::contains_key(ALICE, (collection_id, item_id)));
+		assert!(!AccountItems::<Test>::contains_key(BOB, (collection_id, item_id)));
+	});
+}
+
+#[test]
+fn transfers_require_the_owner_or_a_delegate() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(ALICE, collection_id);
+
+		assert_noop!(
+			NftMaker::transfer(Origin::signed(BOB), collection_id, item_id, BOB),
+			Error::<Test>::NotItemOwner
+		);
+		assert_noop!(
+			NftMaker::transfer_from(Origin::signed(BOB), collection_id, item_id, BOB),
+			Error::<Test>::NotApproved
+		);
+		assert_noop!(
+			NftMaker::approve(Origin::signed(BOB), collection_id, item_id, BOB),
+			Error::<Test>::NotItemOwner
+		);
+
+		assert_ok!(NftMaker::approve(Origin::signed(ALICE), collection_id, item_id, BOB));
+		assert_ok!(NftMaker::cancel_approval(Origin::signed(ALICE), collection_id, item_id));
+		assert_noop!(
+			NftMaker::cancel_approval(Origin::signed(ALICE), collection_id, item_id),
+			Error::<Test>::ApprovalNotFound
+		);
+		assert_noop!(
+			NftMaker::transfer_from(Origin::signed(BOB), collection_id, item_id, BOB),
+			Error::<Test>::NotApproved
+		);
+
+		assert_ok!(NftMaker::set_operator(Origin::signed(ALICE), BOB, true));
+		assert_ok!(NftMaker::set_operator(Origin::signed(ALICE), BOB, false));
+		assert_noop!(
+			NftMaker::transfer_from(Origin::signed(BOB), collection_id, item_id, BOB),
+			Error::<Test>::NotApproved
+		);
+	});
+}
+
+#[test]
+fn royalties_go_to_the_item_creator_or_the_collection_owner() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		assert_ok!(NftMaker::set_minter(Origin::signed(ALICE), collection_id, BOB, true));
+		assert_ok!(NftMaker::set_mint_permission(
+			Origin::signed(ALICE),
+			collection_id,
+			MintPermission::Minters
+		));
+		let token_uri =
+			TokenUri { location: MetadataLocation::OnChain([0; 32]), content_hash: None };
+		assert_ok!(NftMaker::mint(Origin::signed(BOB), collection_id, token_uri, Some(500)));
+		let creator_royalty = NextItemId::<Test>::get() - 1;
+		let collection_royalty = mint(ALICE, collection_id);
+
+		assert_eq!(NftMaker::royalty(&collection_id, &collection_royalty), None);
+		assert_ok!(NftMaker::set_collection_royalty(Origin::signed(ALICE), collection_id, 250));
+
+		assert_eq!(
+			NftMaker::royalty(&collection_id, &creator_royalty),
+			Some((BOB, Permill::from_percent(5)))
+		);
+		assert_eq!(
+			NftMaker::royalty(&collection_id, &collection_royalty),
+			Some((ALICE, Permill::from_parts(25_000)))
+		);
+	});
+}
+
+#[test]
+fn royalties_are_capped() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let token_uri =
+			TokenUri { location: MetadataLocation::OnChain([0; 32]), content_hash: None };
+
+		assert_noop!(
+			NftMaker::mint(Origin::signed(ALICE), collection_id, token_uri, Some(2_501)),
+			Error::<Test>::RoyaltyTooHigh
+		);
+		assert_noop!(
+			NftMaker::set_collection_royalty(Origin::signed(ALICE), collection_id, 2_501),
+			Error::<Test>::RoyaltyTooHigh
+		);
+		assert_noop!(
+			NftMaker::set_collection_royalty(Origin::signed(BOB), collection_id, 100),
+			Error::<Test>::NotCollectionOwner
+		);
+	});
+}
+
+#[test]
+fn items_of_pages_through_the_items_an_account_owns() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let mut minted: Vec<_> =
+			(0..3).map(|_| (collection_id, mint(ALICE, collection_id))).collect();
+
+		let mut pages = NftMaker::items_of(&ALICE, 0, 2);
+		assert_eq!(pages.len(), 2);
+		pages.extend(NftMaker::items_of(&ALICE, 2, 2));
+		pages.sort();
+		minted.sort();
+		assert_eq!(pages, minted);
+		assert!(NftMaker::items_of(&ALICE, 3, 2).is_empty());
+	});
+}
+
+#[test]
+fn items_of_follows_transfers_and_burns() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let transferred = mint(ALICE, collection_id);
+		let burned = mint(ALICE, collection_id);
+
+		assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, transferred, BOB));
+		assert_ok!(NftMaker::burn(Origin::signed(ALICE), collection_id, burned));
+
+		assert!(NftMaker::items_of(&ALICE, 0, 10).is_empty());
+		assert_eq!(NftMaker::items_of(&BOB, 0, 10), vec![(collection_id, transferred)]);
+		assert!(NftMaker::items_of(&CHARLIE, 0, 10).is_empty());
+	});
+}
+
+#[test]
+fn attributes_can_be_set_and_cleared_by_the_collection_owner() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(BOB, collection_id);
+		let key: AttributeKey<Test> = b"level".to_vec().try_into().unwrap();
+		let value = b"3".to_vec().try_into().unwrap();
+
+		assert_ok!(NftMaker::set_attribute(
+			Origin::signed(ALICE),
+			collection_id,
+			item_id,
+			key.clone(),
+			value
+		));
+		assert_eq!(Attributes::<Test>::get((collection_id, item_id, &key)).unwrap().to_vec(), b"3");
+		// A byte per byte of the key and the value
+		assert_eq!(Balances::reserved_balance(ALICE), 6);
+
+		assert_ok!(NftMaker::clear_attribute(
+			Origin::signed(ALICE),
+			collection_id,
+			item_id,
+			key.clone()
+		));
+		assert!(!Attributes::<Test>::contains_key((collection_id, item_id, &key)));
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_noop!(
+			NftMaker::clear_attribute(Origin::signed(ALICE), collection_id, item_id, key),
+			Error::<Test>::AttributeNotFound
+		);
+	});
+}
+
+#[test]
+fn frozen_or_foreign_attributes_cant_change() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(ALICE, collection_id);
+		let key: AttributeKey<Test> = b"level".to_vec().try_into().unwrap();
+		let value: BoundedVec<u8, _> = b"3".to_vec().try_into().unwrap();
+
+		assert_noop!(
+			NftMaker::set_attribute(
+				Origin::signed(BOB),
+				collection_id,
+				item_id,
+				key.clone(),
+				value.clone()
+			),
+			Error::<Test>::NoMetadataPermission
+		);
+		assert_ok!(NftMaker::freeze_item(
+			Origin::signed(ALICE),
+			collection_id,
+			item_id,
+			true,
+			true
+		));
+		assert_noop!(
+			NftMaker::set_attribute(Origin::signed(ALICE), collection_id, item_id, key, value),
+			Error::<Test>::AttributesFrozen
+		);
+		let token_uri =
+			TokenUri { location: MetadataLocation::OnChain([1; 32]), content_hash: None };
+		assert_noop!(
+			NftMaker::set_token_uri(Origin::signed(ALICE), collection_id, item_id, token_uri),
+			Error::<Test>::TokenUriFrozen
+		);
+	});
+}
+
+#[test]
+fn genesis_ids_are_skipped_by_later_collections_and_items() {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(ALICE, 1_000_000)] }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+	let uri = [&b"ipfs://"[..], CID_V1, b"/7.json"].concat();
+	GenesisConfig::<Test> {
+		collections: vec![(5, ALICE, b"meta".to_vec(), Some(1))],
+		items: vec![(5, 7, BOB, uri.clone())],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	let mut ext: sp_io::TestExternalities = storage.into();
+
+	ext.execute_with(|| {
+		assert_eq!(Items::<Test>::get(5, 7).unwrap().owner, BOB);
+		assert_eq!(NftMaker::token_uri(5, 7), Some(uri));
+		assert_eq!(NextCollectionId::<Test>::get(), 6);
+		assert_eq!(NextItemId::<Test>::get(), 8);
+
+		let collection_id = create_collection(ALICE);
+		assert_eq!(collection_id, 6);
+		assert_eq!(mint(ALICE, collection_id), 8);
+	});
+}
+
+#[test]
+fn ids_in_use_and_full_collections_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(ALICE, collection_id);
+
+		assert_noop!(
+			Pallet::<Test>::do_create_collection(
+				collection_id,
+				BOB,
+				Default::default(),
+				None,
+				MintPermission::Owner,
+				false
+			),
+			Error::<Test>::CollectionExists
+		);
+		assert_noop!(
+			Pallet::<Test>::do_mint(collection_id, item_id, BOB, BOB, None, None, None),
+			Error::<Test>::ItemExists
+		);
+
+		assert_ok!(NftMaker::create_collection(
+			Origin::signed(ALICE),
+			Default::default(),
+			Some(1),
+			MintPermission::Owner
+		));
+		let limited = collection_id + 1;
+		mint(ALICE, limited);
+		let token_uri =
+			TokenUri { location: MetadataLocation::OnChain([0; 32]), content_hash: None };
+		assert_noop!(
+			NftMaker::mint(Origin::signed(ALICE), limited, token_uri, None),
+			Error::<Test>::MaxSupplyReached
+		);
+	});
+}
+
+#[test]
+fn nonfungibles_traits_create_mint_and_move_items() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(<NftMaker as Create<_>>::create_collection(&3, &ALICE, &BOB));
+		assert_eq!(<NftMaker as Inspect<_>>::collection_owner(&3), Some(ALICE));
+
+		assert_ok!(<NftMaker as Mutate<_>>::mint_into(&3, &1, &BOB));
+		assert_ok!(<NftMaker as Mutate<_>>::set_attribute(&3, &1, b"level", b"2"));
+		assert_eq!(<NftMaker as Inspect<_>>::attribute(&3, &1, b"level"), Some(b"2".to_vec()));
+		assert!(<NftMaker as Inspect<_>>::can_transfer(&3, &1));
+
+		assert_ok!(<NftMaker as Transfer<_>>::transfer(&3, &1, &CHARLIE));
+		assert_eq!(<NftMaker as Inspect<_>>::owner(&3, &1), Some(CHARLIE));
+		assert_ok!(<NftMaker as Mutate<_>>::burn(&3, &1, Some(&CHARLIE)));
+		assert_eq!(<NftMaker as Inspect<_>>::owner(&3, &1), None);
+		assert_eq!(NextItemId::<Test>::get(), 2);
+	});
+}
+
+#[test]
+fn nonfungibles_traits_keep_the_pallet_checks() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(ALICE, collection_id);
+
+		assert_noop!(
+			<NftMaker as Create<_>>::create_collection(&collection_id, &BOB, &BOB),
+			Error::<Test>::CollectionExists
+		);
+		assert_noop!(
+			<NftMaker as Mutate<_>>::burn(&collection_id, &item_id, Some(&BOB)),
+			Error::<Test>::NotItemOwner
+		);
+		assert_noop!(
+			<NftMaker as Mutate<_>>::set_attribute(&collection_id, &item_id, &[0; 17], b"2"),
+			Error::<Test>::AttributeKeyTooLong
+		);
+
+		assert_ok!(NftMaker::lock(&collection_id, &item_id, *b"stakings"));
+		assert!(!<NftMaker as Inspect<_>>::can_transfer(&collection_id, &item_id));
+		assert_noop!(
+			<NftMaker as Transfer<_>>::transfer(&collection_id, &item_id, &BOB),
+			Error::<Test>::Locked
+		);
+	});
+}
+
+#[test]
+fn soulbound_items_can_only_be_handed_out_by_the_collection_owner() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let badge = mint(ALICE, collection_id);
+		let other = mint(ALICE, collection_id);
+
+		assert_noop!(
+			NftMaker::set_soulbound(Origin::signed(BOB), collection_id, Some(badge)),
+			Error::<Test>::NotCollectionOwner
+		);
+		assert_ok!(NftMaker::set_soulbound(Origin::signed(ALICE), collection_id, Some(badge)));
+		assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, badge, BOB));
+		assert_noop!(
+			NftMaker::transfer(Origin::signed(BOB), collection_id, badge, CHARLIE),
+			Error::<Test>::Soulbound
+		);
+
+		assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, other, BOB));
+		assert_ok!(NftMaker::set_soulbound(Origin::signed(ALICE), collection_id, None));
+		assert_noop!(
+			NftMaker::transfer(Origin::signed(BOB), collection_id, other, CHARLIE),
+			Error::<Test>::Soulbound
+		);
+	});
+}
+
+#[test]
+fn locked_items_cant_move_until_every_lock_is_released() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(ALICE, collection_id);
+
+		assert_ok!(NftMaker::lock(&collection_id, &item_id, *b"stakings"));
+		assert_ok!(NftMaker::lock(&collection_id, &item_id, *b"mktplace"));
+		assert_noop!(
+			NftMaker::lock(&collection_id, &item_id, *b"stakings"),
+			Error::<Test>::AlreadyLocked
+		);
+
+		assert_ok!(NftMaker::unlock(&collection_id, &item_id, *b"stakings"));
+		assert_noop!(
+			NftMaker::transfer(Origin::signed(ALICE), collection_id, item_id, BOB),
+			Error::<Test>::Locked
+		);
+		assert_noop!(
+			NftMaker::unlock(&collection_id, &item_id, *b"stakings"),
+			Error::<Test>::LockNotFound
+		);
+
+		assert_ok!(NftMaker::unlock(&collection_id, &item_id, *b"mktplace"));
+		assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, item_id, BOB));
+	});
+}
+
+#[test]
+fn game_authorities_change_the_attributes_they_were_granted() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(ALICE, collection_id);
+		let xp: AttributeKey<Test> = b"xp".to_vec().try_into().unwrap();
+		let value: BoundedVec<u8, _> = b"120".to_vec().try_into().unwrap();
+		assert_ok!(NftMaker::set_game_authority(
+			Origin::signed(ALICE),
+			collection_id,
+			BOB,
+			xp.clone(),
+			true
+		));
+
+		assert_ok!(NftMaker::set_attribute(
+			Origin::signed(BOB),
+			collection_id,
+			item_id,
+			xp.clone(),
+			value.clone()
+		));
+		System::assert_last_event(Event::NftMaker(crate::Event::AttributeChangedByAuthority(
+			collection_id,
+			item_id,
+			xp.clone(),
+			Some(value),
+			BOB,
+		)));
+		assert_ok!(NftMaker::clear_attribute(Origin::signed(BOB), collection_id, item_id, xp));
+		assert_eq!(Balances::reserved_balance(BOB), 0);
+	});
+}
+
+#[test]
+fn game_authorities_are_limited_to_their_keys_until_revoked() {
+	new_test_ext().execute_with(|| {
+		let collection_id = create_collection(ALICE);
+		let item_id = mint(ALICE, collection_id);
+		let xp: AttributeKey<Test> = b"xp".to_vec().try_into().unwrap();
+		let level: AttributeKey<Test> = b"level".to_vec().try_into().unwrap();
+		let value: BoundedVec<u8, _> = b"2".to_vec().try_into().unwrap();
+
+		assert_noop!(
+			NftMaker::set_game_authority(Origin::signed(BOB), collection_id, BOB, xp.clone(), true),
+			Error::<Test>::NotCollectionOwner
+		);
+		assert_ok!(NftMaker::set_game_authority(
+			Origin::signed(ALICE),
+			collection_id,
+			BOB,
+			xp.clone(),
+			true
+		));
+		assert_noop!(
+			NftMaker::set_attribute(
+				Origin::signed(BOB),
+				collection_id,
+				item_id,
+				level,
+				value.clone()
+			),
+			Error::<Test>::NoMetadataPermission
+		);
+
+		assert_ok!(NftMaker::set_game_authority(
+			Origin::signed(ALICE),
+			collection_id,
+			BOB,
+			xp.clone(),
+			false
+		));
+		assert_noop!(
+			NftMaker::set_attribute(Origin::signed(BOB), collection_id, item_id, xp, value),
+			Error::<Test>::NoMetadataPermission
+		);
 	});
 }
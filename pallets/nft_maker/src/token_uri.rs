@@ -0,0 +1,261 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	traits::Get, BoundedVec, CloneNoBound, DebugNoBound, EqNoBound, PartialEqNoBound,
+};
+use scale_info::TypeInfo;
+use sp_std::vec::Vec;
+
+const IPFS_SCHEME: &[u8] = b"ipfs://";
+const HTTPS_SCHEME: &[u8] = b"https://";
+const ARWEAVE_SCHEME: &[u8] = b"ar://";
+const ON_CHAIN_SCHEME: &[u8] = b"onchain://0x";
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// Length of a base64url encoded Arweave transaction ID
+const ARWEAVE_ID_LEN: usize = 43;
+/// Length of a base58 encoded CIDv0, always a sha2-256 multihash starting with `Qm`
+const CID_V0_LEN: usize = 46;
+/// Shortest CIDv1 accepted, a multibase prefix with a version, codec and short multihash
+const MIN_CID_V1_LEN: usize = 8;
+
+/// Where the metadata JSON of an item is stored
+#[derive(
+	CloneNoBound, Encode, Decode, EqNoBound, PartialEqNoBound, DebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(Limit))]
+pub enum MetadataLocation<Limit: Get<u32>> {
+	/// An IPFS CIDv0 or base32/base58 CIDv1, optionally followed by a path, e.g. `<cid>/1.json`
+	Ipfs(BoundedVec<u8, Limit>),
+	/// A full `https://` URL
+	Https(BoundedVec<u8, Limit>),
+	/// Blake2-256 hash of a blob stored on chain
+	OnChain([u8; 32]),
+	/// An Arweave transaction ID, optionally followed by a path
+	Arweave(BoundedVec<u8, Limit>),
+}
+
+/// Metadata reference of an item
+#[derive(
+	CloneNoBound, Encode, Decode, EqNoBound, PartialEqNoBound, DebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(Limit))]
+pub struct TokenUri<Limit: Get<u32>> {
+	pub location: MetadataLocation<Limit>,
+	/// Blake2-256 hash of the metadata JSON, so clients can verify what they fetched
+	pub content_hash: Option<[u8; 32]>,
+}
+
+impl<Limit: Get<u32>> MetadataLocation<Limit> {
+	/// Whether the location is well formed. CIDs are only checked for their encoding, not
+	/// decoded.
+	pub fn is_valid(&self) -> bool {
+		match self {
+			Self::Ipfs(path) => {
+				let (cid, rest) = split_path(path);
+				is_valid_cid(cid) && is_valid_path(rest)
+			},
+			Self::Https(url) => is_valid_https_url(url),
+			Self::OnChain(_) => true,
+			Self::Arweave(path) => {
+				let (id, rest) = split_path(path);
+				id.len() == ARWEAVE_ID_LEN && id.iter().all(is_base64url) && is_valid_path(rest)
+			},
+		}
+	}
+
+	/// The location as a URI, e.g. `ipfs://<cid>/1.json`, `ar://<id>` or `onchain://0x<hash>`
+	pub fn to_uri(&self) -> Vec<u8> {
+		match self {
+			Self::Ipfs(path) => [IPFS_SCHEME, path.as_slice()].concat(),
+			Self::Https(url) => url.to_vec(),
+			Self::OnChain(hash) => {
+				let mut uri = ON_CHAIN_SCHEME.to_vec();
+				for byte in hash {
+					uri.push(hex_digit(byte >> 4));
+					uri.push(hex_digit(byte & 0xf));
+				}
+				uri
+			},
+			Self::Arweave(path) => [ARWEAVE_SCHEME, path.as_slice()].concat(),
+		}
+	}
+
+	/// Parse a URI in the form returned by `to_uri`. `None` if it's unknown, too long or
+	/// malformed.
+	pub fn from_uri(uri: &[u8]) -> Option<Self> {
+		let location = if let Some(path) = uri.strip_prefix(IPFS_SCHEME) {
+			Self::Ipfs(path.to_vec().try_into().ok()?)
+		} else if uri.starts_with(HTTPS_SCHEME) {
+			Self::Https(uri.to_vec().try_into().ok()?)
+		} else if let Some(path) = uri.strip_prefix(ARWEAVE_SCHEME) {
+			Self::Arweave(path.to_vec().try_into().ok()?)
+		} else if let Some(hex) = uri.strip_prefix(ON_CHAIN_SCHEME) {
+			let mut hash = [0u8; 32];
+			if hex.len() != hash.len() * 2 {
+				return None
+			}
+			for (byte, digits) in hash.iter_mut().zip(hex.chunks(2)) {
+				*byte = hex_value(digits[0])? << 4 | hex_value(digits[1])?;
+			}
+			Self::OnChain(hash)
+		} else {
+			return None
+		};
+		location.is_valid().then(|| location)
+	}
+
+	/// Whether the location is a directory, i.e. its path ends with `/`. Appending to any other
+	/// location could change its CID, Arweave ID or host instead of its path.
+	pub fn is_directory(&self) -> bool {
+		match self {
+			Self::Ipfs(path) | Self::Https(path) | Self::Arweave(path) => path.ends_with(b"/"),
+			Self::OnChain(_) => false,
+		}
+	}
+
+	/// The location with `suffix` appended to its path, e.g. an item ID to a base directory.
+	/// `None` if the location isn't a directory or the result is too long.
+	pub fn with_suffix(&self, suffix: &[u8]) -> Option<Self> {
+		if !self.is_directory() {
+			return None
+		}
+		let append = |path: &BoundedVec<u8, Limit>| -> Option<BoundedVec<u8, Limit>> {
+			[path.as_slice(), suffix].concat().try_into().ok()
+		};
+		match self {
+			Self::Ipfs(path) => Some(Self::Ipfs(append(path)?)),
+			Self::Https(url) => Some(Self::Https(append(url)?)),
+			Self::OnChain(_) => None,
+			Self::Arweave(path) => Some(Self::Arweave(append(path)?)),
+		}
+	}
+}
+
+/// Split `<id>/<path>` into the ID and the rest, including the leading `/`
+fn split_path(path: &[u8]) -> (&[u8], &[u8]) {
+	let end = path.iter().position(|c| *c == b'/').unwrap_or(path.len());
+	path.split_at(end)
+}
+
+/// Printable ASCII without spaces, as allowed in a URI after percent-encoding
+fn is_valid_path(path: &[u8]) -> bool {
+	path.iter().all(|c| c.is_ascii_graphic())
+}
+
+fn is_valid_cid(cid: &[u8]) -> bool {
+	if cid.len() == CID_V0_LEN && cid.starts_with(b"Qm") {
+		return cid.iter().all(|c| BASE58_ALPHABET.contains(c))
+	}
+	if cid.len() < MIN_CID_V1_LEN {
+		return false
+	}
+	match cid[0] {
+		b'b' => cid[1..].iter().all(|c| c.is_ascii_lowercase() || (b'2'..=b'7').contains(c)),
+		b'z' => cid[1..].iter().all(|c| BASE58_ALPHABET.contains(c)),
+		_ => false,
+	}
+}
+
+fn is_valid_https_url(url: &[u8]) -> bool {
+	let rest = match url.strip_prefix(HTTPS_SCHEME) {
+		Some(rest) => rest,
+		None => return false,
+	};
+	let host_len = rest.iter().position(|c| matches!(*c, b'/' | b'?' | b'#')).unwrap_or(rest.len());
+	let (host, path) = rest.split_at(host_len);
+	!host.is_empty() &&
+		host.iter().all(|c| c.is_ascii_alphanumeric() || matches!(*c, b'.' | b'-' | b':')) &&
+		is_valid_path(path)
+}
+
+fn is_base64url(c: &u8) -> bool {
+	c.is_ascii_alphanumeric() || matches!(*c, b'-' | b'_')
+}
+
+fn hex_digit(value: u8) -> u8 {
+	match value {
+		0..=9 => b'0' + value,
+		_ => b'a' + value - 10,
+	}
+}
+
+fn hex_value(digit: u8) -> Option<u8> {
+	match digit {
+		b'0'..=b'9' => Some(digit - b'0'),
+		b'a'..=b'f' => Some(digit - b'a' + 10),
+		b'A'..=b'F' => Some(digit - b'A' + 10),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::traits::ConstU32;
+
+	type Location = MetadataLocation<ConstU32<128>>;
+
+	const CID_V0: &[u8] = b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+	const CID_V1: &[u8] = b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+	#[test]
+	fn test_ipfs_locations() {
+		assert!(Location::from_uri(&[IPFS_SCHEME, CID_V0].concat()).is_some());
+		assert!(Location::from_uri(&[IPFS_SCHEME, CID_V1, b"/1.json"].concat()).is_some());
+		assert!(Location::from_uri(b"ipfs://paraverse/heroes").is_none());
+		assert!(Location::from_uri(&[IPFS_SCHEME, CID_V1, b"/a b"].concat()).is_none());
+	}
+
+	#[test]
+	fn test_https_locations() {
+		assert!(Location::from_uri(b"https://example.com/nfts/1.json").is_some());
+		assert!(Location::from_uri(b"https://localhost:8080").is_some());
+		assert!(Location::from_uri(b"https:///1.json").is_none());
+		assert!(Location::from_uri(b"http://example.com/1.json").is_none());
+	}
+
+	#[test]
+	fn test_arweave_locations() {
+		let id = b"bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U";
+		assert!(Location::from_uri(&[ARWEAVE_SCHEME, id].concat()).is_some());
+		assert!(Location::from_uri(&[ARWEAVE_SCHEME, &id[1..]].concat()).is_none());
+	}
+
+	#[test]
+	fn test_uri_round_trip() {
+		let uris: [&[u8]; 3] = [
+			&[IPFS_SCHEME, CID_V1, b"/7"].concat(),
+			b"https://example.com/7",
+			b"onchain://0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+		];
+		for uri in uris {
+			let location = Location::from_uri(uri).unwrap();
+			assert_eq!(location.to_uri(), uri.to_vec());
+		}
+	}
+
+	#[test]
+	fn test_with_suffix() {
+		let base = Location::from_uri(&[IPFS_SCHEME, CID_V1, b"/"].concat()).unwrap();
+
+		let location = base.with_suffix(b"42").unwrap();
+
+		assert_eq!(location.to_uri(), [IPFS_SCHEME, CID_V1, b"/42"].concat());
+		assert_eq!(Location::OnChain([0; 32]).with_suffix(b"42"), None);
+	}
+
+	#[test]
+	fn test_with_suffix_requires_a_directory() {
+		let cid = Location::from_uri(&[IPFS_SCHEME, CID_V1].concat()).unwrap();
+		let host = Location::from_uri(b"https://example.com").unwrap();
+		let file = Location::from_uri(b"https://example.com/nfts").unwrap();
+
+		assert_eq!(cid.with_suffix(b"42"), None);
+		assert_eq!(host.with_suffix(b"42"), None);
+		assert_eq!(file.with_suffix(b"42"), None);
+		assert_eq!(
+			Location::from_uri(b"https://example.com/nfts/").unwrap().with_suffix(b"42"),
+			Location::from_uri(b"https://example.com/nfts/42")
+		);
+	}
+}
@@ -1,10 +1,80 @@
 use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::{traits::Get, BoundedVec};
+use frame_support::{traits::Get, BoundedVec, RuntimeDebug};
 use scale_info::TypeInfo;
+use sp_std::vec::Vec;
+
+use crate::token_uri::TokenUri;
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, Default, TypeInfo, MaxEncodedLen)]
 #[scale_info(skip_type_params(TokenURILimit))]
 pub struct ItemDetails<AccountId, TokenURILimit: Get<u32>> {
 	pub(super) owner: AccountId,
-	pub(super) token_uri: BoundedVec<u8, TokenURILimit>,
+	/// `None` for items minted by other pallets, which keep their metadata in attributes
+	pub(super) token_uri: Option<TokenUri<TokenURILimit>>,
+	pub(super) creator: AccountId,
+	/// Royalty paid to the creator in basis points. `None` uses the collection's royalty.
+	pub(super) royalty: Option<u16>,
+	/// Number of attributes set on the item
+	pub(super) attributes: u32,
+	/// Once set the token URI can't change
+	pub(super) uri_frozen: bool,
+	/// Once set the attributes can't change
+	pub(super) attributes_frozen: bool,
+	/// Soulbound items can't be transferred once they leave the collection owner
+	pub(super) soulbound: bool,
+}
+
+/// Who may mint items into a collection
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum MintPermission {
+	/// Only the collection owner
+	Owner,
+	/// The collection owner and the accounts in `Minters`
+	Minters,
+	/// Any signed account
+	Public,
+}
+
+/// Token URIs of the items of a `mint_batch`
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub enum BatchTokenUris<TokenURI, Location> {
+	/// One token URI per recipient
+	Explicit(Vec<TokenURI>),
+	/// A base directory the item ID is appended to, e.g. `ipfs://<cid>/` for `ipfs://<cid>/42`.
+	/// It must end with `/`. The items have no content hash.
+	Sequential(Location),
+}
+
+/// An item a collection owner signs off-chain for anyone to mint with `redeem_voucher`, so
+/// creators don't pay to mint items nobody buys. The signature covers the genesis hash of the
+/// chain followed by the voucher, see `Pallet::voucher_payload`.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct MintVoucher<CollectionId, TokenURI, AssetId, Balance, BlockNumber> {
+	pub collection_id: CollectionId,
+	pub token_uri: TokenURI,
+	/// Royalty of the item in basis points, see `ItemDetails::royalty`
+	pub royalty: Option<u16>,
+	/// Asset the redeemer pays `price` in
+	pub asset_id: AssetId,
+	pub price: Balance,
+	/// Unique per collection, so a voucher can only be redeemed once
+	pub nonce: u64,
+	/// Last block the voucher can be redeemed in
+	pub expires_at: BlockNumber,
+}
+
+#[derive(Clone, Encode, Decode, Eq, PartialEq, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MetadataLimit))]
+pub struct CollectionDetails<AccountId, MetadataLimit: Get<u32>> {
+	pub(super) owner: AccountId,
+	pub(super) metadata: BoundedVec<u8, MetadataLimit>,
+	/// Most items the collection can hold, or `None` for no limit
+	pub(super) max_supply: Option<u32>,
+	/// Items currently in the collection
+	pub(super) items: u32,
+	pub(super) mint_permission: MintPermission,
+	/// Royalty paid to the collection owner in basis points, for items without their own
+	pub(super) royalty: u16,
+	/// Every item of a soulbound collection is soulbound
+	pub(super) soulbound: bool,
 }
@@ -0,0 +1,72 @@
+//! Weights for pallet_nft_maker.
+//!
+//! These are hand-written estimates, not benchmark results: the storage accesses are counted from
+//! the extrinsics, and the execution times are guesses. Run the benchmarks in `benchmarking.rs` on
+//! reference hardware and replace this file with their output:
+//! `./target/release/node-template benchmark pallet --chain dev --pallet pallet_nft_maker
+//! --extrinsic '*' --steps 50 --repeat 20`
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+pub trait WeightInfo {
+	fn mint() -> Weight;
+	fn mint_batch(n: u32) -> Weight;
+}
+
+/// Weights for pallet_nft_maker using the node's database weights.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: NFTMaker Collections (r:1 w:1)
+	// Storage: NFTMaker Minters (r:1 w:0)
+	// Storage: NFTMaker NextItemId (r:1 w:1)
+	// Storage: NFTMaker Items (r:1 w:1)
+	// Storage: NFTMaker DepositOverrides (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: NFTMaker ItemDeposits (r:0 w:1)
+	// Storage: NFTMaker AccountItems (r:0 w:1)
+	fn mint() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: NFTMaker Collections (r:1 w:0)
+	// Storage: NFTMaker Minters (r:1 w:0)
+	// Per recipient:
+	// Storage: NFTMaker Collections (r:1 w:1)
+	// Storage: NFTMaker NextItemId (r:1 w:1)
+	// Storage: NFTMaker Items (r:1 w:1)
+	// Storage: NFTMaker DepositOverrides (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: NFTMaker ItemDeposits (r:0 w:1)
+	// Storage: NFTMaker AccountItems (r:0 w:1)
+	fn mint_batch(n: u32) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((15_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(n as Weight)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn mint() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn mint_batch(n: u32) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((15_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(n as Weight)))
+	}
+}
@@ -1,6 +1,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
+pub mod migrations;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
 mod types;
 
 #[frame_support::pallet]
@@ -8,17 +13,31 @@ pub mod pallet {
 	use frame_support::{
 		ensure,
 		pallet_prelude::*,
-		traits::fungibles::{Inspect, InspectMetadata, Mutate, Transfer},
+		traits::{
+			fungibles::{Inspect, InspectMetadata, Mutate, Transfer},
+			LockIdentifier,
+		},
 	};
 	use frame_system::pallet_prelude::*;
-	use pallet_custom_traits::{Ownership, Transfer as ItemTransfer};
-	use sp_runtime::traits::{AccountIdConversion, AtLeast32BitUnsigned};
+	use pallet_custom_traits::{
+		Lockable, OnBurn, Ownership, Rentable, Royalty, Transfer as ItemTransfer,
+	};
+	use sp_runtime::{
+		traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedMul, One, Saturating, Zero},
+		PerThing,
+	};
 
-	use crate::types::SaleItem;
+	use crate::types::{RentalListing, SaleItem};
 
 	type AssetIdOf<T: Config> = <T::Assets as Inspect<T::AccountId>>::AssetId;
 	type BalanceOf<T: Config> = <T::Assets as Inspect<T::AccountId>>::Balance;
 
+	/// Lock taken on listed items, so they can't move or be listed twice until the sale ends
+	pub(crate) const MARKETPLACE_LOCK: LockIdentifier = *b"mktplace";
+
+	/// Lock taken on rented items, so they can't move or be burned until the rental expires
+	pub(crate) const RENTAL_LOCK: LockIdentifier = *b"mktrentl";
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
@@ -26,13 +45,14 @@ pub mod pallet {
 			+ Transfer<Self::AccountId>
 			+ Mutate<Self::AccountId>
 			+ InspectMetadata<Self::AccountId>;
+		type CollectionId: Member + Parameter + MaxEncodedLen + Copy;
 		type ItemId: Member + Parameter + MaxEncodedLen + Copy + AtLeast32BitUnsigned;
-		type SaleId: Member + Parameter + MaxEncodedLen + Copy + AtLeast32BitUnsigned;
-		type NFT: Ownership<Self::ItemId, Self::AccountId>
-			+ ItemTransfer<Self::ItemId, Self::AccountId>;
-
-		#[pallet::constant]
-		type MarketplaceAccount: Get<frame_support::PalletId>;
+		type SaleId: Member + Parameter + MaxEncodedLen + Copy + AtLeast32BitUnsigned + Default;
+		type NFT: Ownership<Self::CollectionId, Self::ItemId, Self::AccountId>
+			+ ItemTransfer<Self::CollectionId, Self::ItemId, Self::AccountId>
+			+ Royalty<Self::CollectionId, Self::ItemId, Self::AccountId>
+			+ Lockable<Self::CollectionId, Self::ItemId>
+			+ Rentable<Self::CollectionId, Self::ItemId, Self::AccountId, Self::BlockNumber>;
 	}
 
 	#[pallet::storage]
@@ -41,26 +61,96 @@ pub mod pallet {
 		_,
 		Blake2_128Concat,
 		T::SaleId,
-		SaleItem<T::AccountId, T::SaleId, AssetIdOf<T>, T::ItemId, BalanceOf<T>>,
+		SaleItem<T::AccountId, T::SaleId, AssetIdOf<T>, T::CollectionId, T::ItemId, BalanceOf<T>>,
 		OptionQuery,
 	>;
 
+	/// The sale of each listed item. An item can only be listed once.
+	#[pallet::storage]
+	pub(super) type ItemSales<T: Config> =
+		StorageMap<_, Blake2_128Concat, (T::CollectionId, T::ItemId), T::SaleId, OptionQuery>;
+
+	/// Items listed for rent. Rental listings share their IDs with sales.
 	#[pallet::storage]
-	pub(super) type Counter<T: Config> =
-		CountedStorageMap<_, Twox128, T::AccountId, T::SaleId, OptionQuery>;
+	pub(super) type Rentals<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::SaleId,
+		RentalListing<
+			T::AccountId,
+			T::CollectionId,
+			T::ItemId,
+			AssetIdOf<T>,
+			BalanceOf<T>,
+			T::BlockNumber,
+		>,
+		OptionQuery,
+	>;
+
+	/// The rental listing of each item. An item can only be listed for rent once.
+	#[pallet::storage]
+	pub(super) type ItemRentals<T: Config> =
+		StorageMap<_, Blake2_128Concat, (T::CollectionId, T::ItemId), T::SaleId, OptionQuery>;
+
+	/// Rented items to unlock at the block their rental expires, as block -> (collection ID,
+	/// item ID)
+	#[pallet::storage]
+	pub(super) type RentalExpiries<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		(T::CollectionId, T::ItemId),
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	pub(super) type NextSaleId<T: Config> = StorageValue<_, T::SaleId, ValueQuery>;
 
 	impl<T: Config> Pallet<T> {
-		/// TODO -> HOW TO CACHE THIS ???
-		fn account_id() -> T::AccountId {
-			T::MarketplaceAccount::get().into_account_truncating()
+		/// Take the next free sale ID
+		fn next_sale_id() -> Result<T::SaleId, DispatchError> {
+			let sale_id = NextSaleId::<T>::get();
+			let next = sale_id.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
+			NextSaleId::<T>::put(next);
+			Ok(sale_id)
+		}
+
+		/// Remove a sale and release its item
+		fn remove_sale(
+			sale_id: T::SaleId,
+			sale: &SaleItem<
+				T::AccountId,
+				T::SaleId,
+				AssetIdOf<T>,
+				T::CollectionId,
+				T::ItemId,
+				BalanceOf<T>,
+			>,
+		) -> DispatchResult {
+			Sales::<T>::remove(sale_id);
+			ItemSales::<T>::remove((sale.collection_id, sale.item_id));
+			T::NFT::unlock(&sale.collection_id, &sale.item_id, MARKETPLACE_LOCK)
 		}
+	}
+
+	impl<T: Config> OnBurn<T::CollectionId, T::ItemId> for Pallet<T> {
+		/// Remove the sale and the rental listing of a burned item. Items for sale and rented items
+		/// are locked, so this only finds items listed for rent and sales left over from before
+		/// listings locked their items.
+		fn on_burn(collection_id: &T::CollectionId, id: &T::ItemId) {
+			if let Some(sale_id) = ItemSales::<T>::take((collection_id, id)) {
+				if let Some(sale) = Sales::<T>::take(sale_id) {
+					Self::deposit_event(Event::<T>::SaleCanceled(sale_id, sale.owner));
+				}
+			}
 
-		fn increment_ids() -> T::SaleId {
-			let key = Self::account_id();
-			let result = Counter::<T>::get(&key).unwrap();
-			let next = result + 1u32.into();
-			Counter::<T>::set(key, next.into());
-			next
+			if let Some(rental_id) = ItemRentals::<T>::take((collection_id, id)) {
+				if let Some(rental) = Rentals::<T>::take(rental_id) {
+					Self::deposit_event(Event::<T>::RentalCanceled(rental_id, rental.owner));
+				}
+			}
 		}
 	}
 
@@ -73,6 +163,13 @@ pub mod pallet {
 		InvalidPrice,
 		SaleNotFound,
 		ItemTTransferFailed,
+		Overflow,
+		NotTransferable,
+		InvalidRentalTerms,
+		RentalNotFound,
+		InvalidPeriods,
+		AlreadyListed,
+		CannotBuyOwnSale,
 	}
 
 	#[pallet::event]
@@ -80,32 +177,78 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		SaleCreated(T::SaleId, T::AccountId),
 		SaleCanceled(T::SaleId, T::AccountId),
-		ItemPurchased(T::ItemId, T::AccountId, BalanceOf<T>),
+		// (sale ID, new price)
+		SalePriceUpdated(T::SaleId, BalanceOf<T>),
+		// (collection ID, item ID, buyer, price)
+		ItemPurchased(T::CollectionId, T::ItemId, T::AccountId, BalanceOf<T>),
+		// (collection ID, item ID, royalty recipient, amount)
+		RoyaltyPaid(T::CollectionId, T::ItemId, T::AccountId, BalanceOf<T>),
+		RentalListed(T::SaleId, T::AccountId),
+		RentalCanceled(T::SaleId, T::AccountId),
+		// (rental ID, renter, price paid, expires at)
+		ItemRented(T::SaleId, T::AccountId, BalanceOf<T>, T::BlockNumber),
 	}
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Unlock the items whose rental expires at this block
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut expired = 0;
+			for ((collection_id, item_id), _) in RentalExpiries::<T>::drain_prefix(now) {
+				expired += 1;
+				if let Err(e) = T::NFT::unlock(&collection_id, &item_id, RENTAL_LOCK) {
+					log::warn!(target: "marketplace", "Rented item not unlocked: {:?}", e);
+				}
+			}
+
+			T::DbWeight::get().reads_writes(1 + expired * 2, expired * 2)
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// List an item for sale at `price` in `asset_id`. The item is locked until it's sold or
+		/// the sale is canceled, so it can only be listed once.
 		#[pallet::weight(5_000_000)]
 		pub fn create_sale(
 			origin: OriginFor<T>,
 			asset_id: AssetIdOf<T>,
+			collection_id: T::CollectionId,
 			item_id: T::ItemId,
 			price: BalanceOf<T>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 
 			ensure!(price > 0u32.into(), Error::<T>::InvalidPrice);
-			ensure!(T::NFT::is_owner(&item_id, &sender), Error::<T>::Unauthorized);
+			ensure!(T::NFT::is_owner(&collection_id, &item_id, &sender), Error::<T>::Unauthorized);
+			ensure!(
+				!ItemSales::<T>::contains_key((collection_id, item_id)) &&
+					!ItemRentals::<T>::contains_key((collection_id, item_id)),
+				Error::<T>::AlreadyListed,
+			);
+			ensure!(T::NFT::can_transfer(&collection_id, &item_id), Error::<T>::NotTransferable);
 			let asset = T::Assets::name(&asset_id);
 			ensure!(asset.len() > 0, Error::<T>::AssetDoesNotExist);
 
-			let next_id = Self::increment_ids();
+			let next_id = Self::next_sale_id()?;
+			T::NFT::lock(&collection_id, &item_id, MARKETPLACE_LOCK)?;
+			ItemSales::<T>::insert((collection_id, item_id), next_id);
 
-			let sale = SaleItem { owner: sender.clone(), id: next_id, asset_id, item_id, price };
+			let sale = SaleItem {
+				owner: sender.clone(),
+				id: next_id,
+				asset_id,
+				collection_id,
+				item_id,
+				price,
+			};
 			Sales::<T>::insert(next_id, sale);
 
 			Self::deposit_event(Event::<T>::SaleCreated(next_id, sender));
@@ -113,31 +256,185 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Buy a listed item. The item is transferred to the buyer before any payment, and the
+		/// whole purchase is reverted if either fails.
 		#[pallet::weight(1_000_000)]
 		pub fn purchase(origin: OriginFor<T>, sale_id: T::SaleId) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			// Check sale exists
-			let sale = Sales::<T>::get(sale_id);
-			ensure!(sale.is_some(), Error::<T>::SaleNotFound);
-			let sale = sale.unwrap();
+			let sale = Sales::<T>::get(sale_id).ok_or(Error::<T>::SaleNotFound)?;
+			ensure!(sale.owner != sender, Error::<T>::CannotBuyOwnSale);
+			ensure!(
+				T::NFT::is_owner(&sale.collection_id, &sale.item_id, &sale.owner),
+				Error::<T>::NotFound,
+			);
 
 			// Check buyer's balance
 			let buyer_balance = T::Assets::balance(sale.asset_id, &sender);
-			ensure!(&sale.price <= &buyer_balance, Error::<T>::InsufficientBalance);
-
-			// Pay the seller
-			T::Assets::transfer(sale.asset_id, &sender, &sale.owner, sale.price, false)?;
+			ensure!(sale.price <= buyer_balance, Error::<T>::InsufficientBalance);
 
-			// Transfer Item
-			let success = T::NFT::transfer(&sale.item_id, &sender);
+			// Release and transfer the item
+			Self::remove_sale(sale_id, &sale)?;
+			let success = T::NFT::transfer(&sale.collection_id, &sale.item_id, &sender);
 			ensure!(success, Error::<T>::ItemTTransferFailed);
 
-			// Emit event
-			Self::deposit_event(Event::<T>::ItemPurchased(sale.item_id, sender, sale.price));
+			// Pay the royalty recipient their share, and the seller the rest. A share too small to
+			// open an account of the asset for the recipient goes to the seller.
+			let mut seller_amount = sale.price;
+			if let Some((recipient, share)) = T::NFT::royalty(&sale.collection_id, &sale.item_id) {
+				let royalty = share.mul_floor(sale.price);
+				let can_receive = royalty >= T::Assets::minimum_balance(sale.asset_id) ||
+					!T::Assets::balance(sale.asset_id, &recipient).is_zero();
+				if recipient != sale.owner && !royalty.is_zero() && can_receive {
+					T::Assets::transfer(sale.asset_id, &sender, &recipient, royalty, false)?;
+					seller_amount = seller_amount.saturating_sub(royalty);
+					Self::deposit_event(Event::<T>::RoyaltyPaid(
+						sale.collection_id,
+						sale.item_id,
+						recipient,
+						royalty,
+					));
+				}
+			}
+			T::Assets::transfer(sale.asset_id, &sender, &sale.owner, seller_amount, false)?;
 
-			// Delete the sale
-			Sales::<T>::remove(sale_id);
+			Self::deposit_event(Event::<T>::ItemPurchased(
+				sale.collection_id,
+				sale.item_id,
+				sender,
+				sale.price,
+			));
+
+			Ok(())
+		}
+
+		/// Take a sale down and unlock its item
+		#[pallet::weight(1_000_000)]
+		pub fn cancel_sale(origin: OriginFor<T>, sale_id: T::SaleId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let sale = Sales::<T>::get(sale_id).ok_or(Error::<T>::SaleNotFound)?;
+			ensure!(sale.owner == sender, Error::<T>::Unauthorized);
+
+			Self::remove_sale(sale_id, &sale)?;
+
+			Self::deposit_event(Event::<T>::SaleCanceled(sale_id, sender));
+
+			Ok(())
+		}
+
+		/// Change the price of a sale, in the same asset
+		#[pallet::weight(1_000_000)]
+		pub fn update_price(
+			origin: OriginFor<T>,
+			sale_id: T::SaleId,
+			price: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(price > 0u32.into(), Error::<T>::InvalidPrice);
+			Sales::<T>::try_mutate(sale_id, |sale| -> DispatchResult {
+				let sale = sale.as_mut().ok_or(Error::<T>::SaleNotFound)?;
+				ensure!(sale.owner == sender, Error::<T>::Unauthorized);
+				sale.price = price;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::SalePriceUpdated(sale_id, price));
+
+			Ok(())
+		}
+
+		/// List an item for rent at `price` per `period` blocks, for up to `max_periods` periods
+		/// at once. The listing stays up for later renters until it is canceled. Items can't be
+		/// listed for rent and for sale at the same time.
+		#[pallet::weight(5_000_000)]
+		pub fn create_rental(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			price: BalanceOf<T>,
+			period: T::BlockNumber,
+			max_periods: u32,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(
+				price > 0u32.into() && !period.is_zero() && max_periods > 0,
+				Error::<T>::InvalidRentalTerms,
+			);
+			ensure!(T::NFT::is_owner(&collection_id, &item_id, &sender), Error::<T>::Unauthorized);
+			ensure!(
+				!ItemRentals::<T>::contains_key((collection_id, item_id)) &&
+					!ItemSales::<T>::contains_key((collection_id, item_id)),
+				Error::<T>::AlreadyListed,
+			);
+			let asset = T::Assets::name(&asset_id);
+			ensure!(asset.len() > 0, Error::<T>::AssetDoesNotExist);
+
+			let rental_id = Self::next_sale_id()?;
+			ItemRentals::<T>::insert((collection_id, item_id), rental_id);
+
+			let rental = RentalListing {
+				owner: sender.clone(),
+				collection_id,
+				item_id,
+				asset_id,
+				price,
+				period,
+				max_periods,
+			};
+			Rentals::<T>::insert(rental_id, rental);
+
+			Self::deposit_event(Event::<T>::RentalListed(rental_id, sender));
+
+			Ok(())
+		}
+
+		#[pallet::weight(1_000_000)]
+		pub fn cancel_rental(origin: OriginFor<T>, rental_id: T::SaleId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let rental = Rentals::<T>::get(rental_id).ok_or(Error::<T>::RentalNotFound)?;
+			ensure!(rental.owner == sender, Error::<T>::Unauthorized);
+
+			Rentals::<T>::remove(rental_id);
+			ItemRentals::<T>::remove((rental.collection_id, rental.item_id));
+
+			Self::deposit_event(Event::<T>::RentalCanceled(rental_id, sender));
+
+			Ok(())
+		}
+
+		/// Rent a listed item for `periods` periods, paying the owner up front. The caller
+		/// becomes the item's user until the rental expires, and the item is locked until then.
+		#[pallet::weight(5_000_000)]
+		pub fn rent(origin: OriginFor<T>, rental_id: T::SaleId, periods: u32) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let rental = Rentals::<T>::get(rental_id).ok_or(Error::<T>::RentalNotFound)?;
+			ensure!(periods > 0 && periods <= rental.max_periods, Error::<T>::InvalidPeriods);
+			ensure!(
+				T::NFT::is_owner(&rental.collection_id, &rental.item_id, &rental.owner),
+				Error::<T>::Unauthorized,
+			);
+
+			let price = rental.price.checked_mul(&periods.into()).ok_or(Error::<T>::Overflow)?;
+			let expires_at = rental
+				.period
+				.checked_mul(&periods.into())
+				.and_then(|duration| {
+					frame_system::Pallet::<T>::block_number().checked_add(&duration)
+				})
+				.ok_or(Error::<T>::Overflow)?;
+
+			T::NFT::set_user(&rental.collection_id, &rental.item_id, &sender, expires_at)?;
+			T::NFT::lock(&rental.collection_id, &rental.item_id, RENTAL_LOCK)?;
+			RentalExpiries::<T>::insert(expires_at, (rental.collection_id, rental.item_id), ());
+			T::Assets::transfer(rental.asset_id, &sender, &rental.owner, price, false)?;
+
+			Self::deposit_event(Event::<T>::ItemRented(rental_id, sender, price, expires_at));
 
 			Ok(())
 		}
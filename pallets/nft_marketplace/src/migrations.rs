@@ -0,0 +1,90 @@
+use crate::{types::SaleItem, Config, ItemSales, NextSaleId, Pallet, Sales, MARKETPLACE_LOCK};
+use frame_support::{
+	storage::migration::remove_storage_prefix,
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+	weights::Weight,
+};
+use pallet_custom_traits::{Lockable, Ownership};
+use sp_runtime::traits::{One, Saturating};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+pub(crate) mod v0 {
+	use codec::{Decode, Encode};
+
+	/// A sale as stored before collections, when items were identified by their ID alone
+	#[derive(Encode, Decode)]
+	pub struct SaleItem<AccountId, Id, AssetId, ItemId, Price> {
+		pub owner: AccountId,
+		pub id: Id,
+		pub item_id: ItemId,
+		pub asset_id: AssetId,
+		pub price: Price,
+	}
+}
+
+/// Moves the sales of the original layout into the current one and locks their items.
+///
+/// Before v1 sales didn't name a collection, their last used ID was kept in a `Counter` map and
+/// listing didn't lock items, so an item could be listed several times or moved after being
+/// listed. Sales are assigned to `LegacyCollection`, the collection the NFT maker migration mints
+/// the original items into, so it must run first. The first sale of each item its seller still
+/// owns is kept, and every other sale is removed.
+pub struct MigrateToV1<T, LegacyCollection>(PhantomData<(T, LegacyCollection)>);
+
+impl<T, LegacyCollection> OnRuntimeUpgrade for MigrateToV1<T, LegacyCollection>
+where
+	T: Config,
+	LegacyCollection: Get<T::CollectionId>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 0 {
+			log::info!(target: "nft_marketplace", "Sales already migrated, skipping");
+			return T::DbWeight::get().reads(1)
+		}
+
+		let pallet = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+		remove_storage_prefix(pallet, b"Counter", &[]);
+		remove_storage_prefix(pallet, b"CounterForCounter", &[]);
+
+		let collection_id = LegacyCollection::get();
+		let mut next_sale_id = NextSaleId::<T>::get();
+		Sales::<T>::translate::<v0::SaleItem<_, _, _, _, _>, _>(|sale_id, sale| {
+			next_sale_id = next_sale_id.max(sale_id.saturating_add(One::one()));
+			Some(SaleItem {
+				owner: sale.owner,
+				id: sale.id,
+				collection_id,
+				item_id: sale.item_id,
+				asset_id: sale.asset_id,
+				price: sale.price,
+			})
+		});
+		NextSaleId::<T>::put(next_sale_id);
+
+		let mut sales: Vec<_> = Sales::<T>::iter().collect();
+		sales.sort_by_key(|(sale_id, _)| *sale_id);
+		let mut removed = 0u64;
+		for (sale_id, sale) in &sales {
+			let item = (sale.collection_id, sale.item_id);
+			let is_listable = !ItemSales::<T>::contains_key(item) &&
+				T::NFT::is_owner(&sale.collection_id, &sale.item_id, &sale.owner) &&
+				T::NFT::lock(&sale.collection_id, &sale.item_id, MARKETPLACE_LOCK).is_ok();
+			if is_listable {
+				ItemSales::<T>::insert(item, sale_id);
+			} else {
+				Sales::<T>::remove(sale_id);
+				removed += 1;
+			}
+		}
+		log::info!(
+			target: "nft_marketplace",
+			"Migrated {} sales, removed {} duplicate or stale ones",
+			sales.len() as u64 - removed,
+			removed
+		);
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		let count = sales.len() as u64;
+		T::DbWeight::get().reads_writes(count * 5 + 2, count * 3 + 4)
+	}
+}
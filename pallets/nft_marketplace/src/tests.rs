@@ -1,20 +1,364 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	migrations::{v0, MigrateToV1},
+	mock::*,
+	types::SaleItem,
+	Error, ItemRentals, ItemSales, NextSaleId, Rentals, Sales,
+};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok, parameter_types,
+	storage::migration::put_storage_value,
+	traits::{
+		tokens::nonfungibles::Inspect as NftInspect, GetStorageVersion, OnRuntimeUpgrade,
+		StorageVersion,
+	},
+	Blake2_128Concat, StorageHasher,
+};
+use pallet_custom_traits::Lockable;
+
+const GOLD: u32 = 0;
+const SILVER: u32 = 1;
+
+parameter_types! {
+	pub const LegacyCollection: u32 = 0;
+}
+
+/// Create GOLD with a name so items can be listed in it, give BOB 1_000 and mint ALICE an item
+fn setup() -> (u32, u32) {
+	assert_ok!(Assets::force_create(Origin::root(), GOLD, ALICE, true, 1));
+	assert_ok!(Assets::force_set_metadata(
+		Origin::root(),
+		GOLD,
+		b"Gold".to_vec(),
+		b"GLD".to_vec(),
+		0,
+		false
+	));
+	assert_ok!(Assets::mint(Origin::signed(ALICE), GOLD, BOB, 1_000));
+	let collection_id = create_collection(ALICE);
+	(collection_id, mint(ALICE, collection_id))
+}
+
+/// An item of a CHARLIE collection with a collection royalty of 10%, owned by ALICE
+fn royalty_item() -> (u32, u32) {
+	let collection_id = create_collection(CHARLIE);
+	assert_ok!(NftMaker::set_collection_royalty(Origin::signed(CHARLIE), collection_id, 1_000));
+	let item_id = mint(CHARLIE, collection_id);
+	assert_ok!(NftMaker::transfer(Origin::signed(CHARLIE), collection_id, item_id, ALICE));
+	(collection_id, item_id)
+}
+
+fn put_baseline_sale(sale_id: u32, owner: u64, item_id: u32) {
+	let sale = v0::SaleItem::<u64, u32, u32, u32, u64> {
+		owner,
+		id: sale_id,
+		item_id,
+		asset_id: GOLD,
+		price: 100,
+	};
+	let key = Blake2_128Concat::hash(&sale_id.encode());
+	put_storage_value(b"Marketplace", b"Sales", &key, sale);
+}
+
+#[test]
+fn create_sale_locks_the_item_and_rejects_duplicates() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = setup();
+
+		assert_ok!(Marketplace::create_sale(
+			Origin::signed(ALICE),
+			GOLD,
+			collection_id,
+			item_id,
+			100
+		));
+
+		assert_eq!(ItemSales::<Test>::get((collection_id, item_id)), Some(0));
+		assert!(NftMaker::is_locked(&collection_id, &item_id));
+		assert_noop!(
+			Marketplace::create_sale(Origin::signed(ALICE), GOLD, collection_id, item_id, 50),
+			Error::<Test>::AlreadyListed
+		);
+		assert_noop!(
+			NftMaker::transfer(Origin::signed(ALICE), collection_id, item_id, CHARLIE),
+			pallet_nft_maker::Error::<Test>::Locked
+		);
+	});
+}
+
+#[test]
+fn purchase_pays_the_seller_and_transfers_the_item() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = setup();
+		assert_ok!(Marketplace::create_sale(
+			Origin::signed(ALICE),
+			GOLD,
+			collection_id,
+			item_id,
+			100
+		));
+
+		assert_ok!(Marketplace::purchase(Origin::signed(BOB), 0));
+
+		assert_eq!(NftMaker::owner(&collection_id, &item_id), Some(BOB));
+		assert!(!NftMaker::is_locked(&collection_id, &item_id));
+		assert_eq!(Assets::balance(GOLD, ALICE), 100);
+		assert_eq!(Assets::balance(GOLD, BOB), 900);
+		assert!(!Sales::<Test>::contains_key(0));
+		assert!(!ItemSales::<Test>::contains_key((collection_id, item_id)));
+	});
+}
+
+#[test]
+fn purchase_pays_the_royalty_to_the_collection_owner() {
+	new_test_ext().execute_with(|| {
+		setup();
+		let (collection_id, item_id) = royalty_item();
+		assert_ok!(Marketplace::create_sale(
+			Origin::signed(ALICE),
+			GOLD,
+			collection_id,
+			item_id,
+			100
+		));
+
+		assert_ok!(Marketplace::purchase(Origin::signed(BOB), 0));
+
+		assert_eq!(Assets::balance(GOLD, CHARLIE), 10);
+		assert_eq!(Assets::balance(GOLD, ALICE), 90);
+		assert_eq!(Assets::balance(GOLD, BOB), 900);
+	});
+}
+
+#[test]
+fn royalties_below_the_minimum_balance_go_to_the_seller() {
+	new_test_ext().execute_with(|| {
+		setup();
+		assert_ok!(Assets::force_create(Origin::root(), SILVER, ALICE, true, 20));
+		assert_ok!(Assets::force_set_metadata(
+			Origin::root(),
+			SILVER,
+			b"Silver".to_vec(),
+			b"SLV".to_vec(),
+			0,
+			false
+		));
+		assert_ok!(Assets::mint(Origin::signed(ALICE), SILVER, BOB, 1_000));
+		let (collection_id, item_id) = royalty_item();
+		assert_ok!(Marketplace::create_sale(
+			Origin::signed(ALICE),
+			SILVER,
+			collection_id,
+			item_id,
+			100
+		));
+
+		assert_ok!(Marketplace::purchase(Origin::signed(BOB), 0));
+
+		assert_eq!(NftMaker::owner(&collection_id, &item_id), Some(BOB));
+		assert_eq!(Assets::balance(SILVER, CHARLIE), 0);
+		assert_eq!(Assets::balance(SILVER, ALICE), 100);
+	});
+}
+
+#[test]
+fn purchase_of_an_item_that_moved_reverts_without_moving_funds() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = setup();
+		// A sale left over from before listings locked their items
+		let sale =
+			SaleItem { owner: ALICE, id: 0, collection_id, item_id, asset_id: GOLD, price: 100 };
+		Sales::<Test>::insert(0, sale);
+		assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, item_id, CHARLIE));
+
+		assert_noop!(Marketplace::purchase(Origin::signed(BOB), 0), Error::<Test>::NotFound);
+
+		assert_eq!(NftMaker::owner(&collection_id, &item_id), Some(CHARLIE));
+		assert_eq!(Assets::balance(GOLD, ALICE), 0);
+		assert_eq!(Assets::balance(GOLD, BOB), 1_000);
+	});
+}
+
+#[test]
+fn purchase_reverts_if_the_item_cant_be_transferred() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = setup();
+		assert_ok!(Marketplace::create_sale(
+			Origin::signed(ALICE),
+			GOLD,
+			collection_id,
+			item_id,
+			100
+		));
+		assert_ok!(NftMaker::lock(&collection_id, &item_id, *b"stakings"));
+
+		assert_noop!(
+			Marketplace::purchase(Origin::signed(BOB), 0),
+			Error::<Test>::ItemTTransferFailed
+		);
+
+		assert_eq!(Assets::balance(GOLD, BOB), 1_000);
+		assert!(ItemSales::<Test>::contains_key((collection_id, item_id)));
+	});
+}
+
+#[test]
+fn only_the_seller_can_cancel_or_reprice_a_sale() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = setup();
+		assert_ok!(Marketplace::create_sale(
+			Origin::signed(ALICE),
+			GOLD,
+			collection_id,
+			item_id,
+			100
+		));
+
+		assert_noop!(Marketplace::cancel_sale(Origin::signed(BOB), 0), Error::<Test>::Unauthorized);
+		assert_noop!(
+			Marketplace::update_price(Origin::signed(BOB), 0, 1),
+			Error::<Test>::Unauthorized
+		);
+
+		assert_ok!(Marketplace::update_price(Origin::signed(ALICE), 0, 200));
+		assert_eq!(Sales::<Test>::get(0).unwrap().price, 200);
+		assert_ok!(Marketplace::cancel_sale(Origin::signed(ALICE), 0));
+		assert!(!NftMaker::is_locked(&collection_id, &item_id));
+		assert!(!ItemSales::<Test>::contains_key((collection_id, item_id)));
+	});
+}
+
+#[test]
+fn items_can_only_be_listed_for_rent_once_and_not_for_sale_too() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = setup();
+		let listed = mint(ALICE, collection_id);
+		assert_ok!(Marketplace::create_rental(
+			Origin::signed(ALICE),
+			GOLD,
+			collection_id,
+			item_id,
+			10,
+			5,
+			2
+		));
+		assert_ok!(Marketplace::create_sale(
+			Origin::signed(ALICE),
+			GOLD,
+			collection_id,
+			listed,
+			100
+		));
+
+		assert_eq!(ItemRentals::<Test>::get((collection_id, item_id)), Some(0));
+		assert_noop!(
+			Marketplace::create_rental(
+				Origin::signed(ALICE),
+				GOLD,
+				collection_id,
+				item_id,
+				10,
+				5,
+				2
+			),
+			Error::<Test>::AlreadyListed
+		);
+		assert_noop!(
+			Marketplace::create_sale(Origin::signed(ALICE), GOLD, collection_id, item_id, 100),
+			Error::<Test>::AlreadyListed
+		);
+		assert_noop!(
+			Marketplace::create_rental(
+				Origin::signed(ALICE),
+				GOLD,
+				collection_id,
+				listed,
+				10,
+				5,
+				2
+			),
+			Error::<Test>::AlreadyListed
+		);
+
+		assert_ok!(Marketplace::cancel_rental(Origin::signed(ALICE), 0));
+		assert!(!ItemRentals::<Test>::contains_key((collection_id, item_id)));
+	});
+}
 
 #[test]
-fn it_works_for_default_value() {
+fn rented_items_stay_locked_until_the_rental_expires() {
 	new_test_ext().execute_with(|| {
-		// Dispatch a signed extrinsic.
-		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
-		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
+		let (collection_id, item_id) = setup();
+		assert_ok!(Marketplace::create_rental(
+			Origin::signed(ALICE),
+			GOLD,
+			collection_id,
+			item_id,
+			10,
+			5,
+			2
+		));
+
+		assert_ok!(Marketplace::rent(Origin::signed(BOB), 0, 2));
+		assert_ok!(Marketplace::cancel_rental(Origin::signed(ALICE), 0));
+
+		assert_eq!(Assets::balance(GOLD, ALICE), 20);
+		assert_noop!(
+			NftMaker::transfer(Origin::signed(ALICE), collection_id, item_id, CHARLIE),
+			pallet_nft_maker::Error::<Test>::Locked
+		);
+		System::set_block_number(10);
+		Marketplace::on_initialize(10);
+		assert!(NftMaker::is_locked(&collection_id, &item_id));
+		System::set_block_number(11);
+		Marketplace::on_initialize(11);
+		assert!(!NftMaker::is_locked(&collection_id, &item_id));
+		assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, item_id, CHARLIE));
 	});
 }
 
 #[test]
-fn correct_error_for_none_value() {
+fn burning_an_item_removes_its_rental_listing() {
 	new_test_ext().execute_with(|| {
-		// Ensure the expected error is thrown when no value is present.
-		assert_noop!(TemplateModule::cause_error(Origin::signed(1)), Error::<Test>::NoneValue);
+		let (collection_id, item_id) = setup();
+		assert_ok!(Marketplace::create_rental(
+			Origin::signed(ALICE),
+			GOLD,
+			collection_id,
+			item_id,
+			10,
+			5,
+			2
+		));
+
+		assert_ok!(NftMaker::burn(Origin::signed(ALICE), collection_id, item_id));
+
+		assert!(!Rentals::<Test>::contains_key(0));
+		assert!(!ItemRentals::<Test>::contains_key((collection_id, item_id)));
+	});
+}
+
+#[test]
+fn migration_keeps_the_first_sale_of_each_item_and_removes_the_rest() {
+	new_test_ext().execute_with(|| {
+		let (collection_id, item_id) = setup();
+		let moved = mint(ALICE, collection_id);
+		assert_ok!(NftMaker::transfer(Origin::signed(ALICE), collection_id, moved, CHARLIE));
+		put_baseline_sale(0, ALICE, item_id);
+		put_baseline_sale(1, ALICE, item_id);
+		put_baseline_sale(2, ALICE, moved);
+
+		MigrateToV1::<Test, LegacyCollection>::on_runtime_upgrade();
+
+		let sale = Sales::<Test>::get(0).unwrap();
+		assert_eq!((sale.collection_id, sale.item_id, sale.owner), (collection_id, item_id, ALICE));
+		assert!(!Sales::<Test>::contains_key(1));
+		assert!(!Sales::<Test>::contains_key(2));
+		assert_eq!(ItemSales::<Test>::get((collection_id, item_id)), Some(0));
+		assert!(!ItemSales::<Test>::contains_key((collection_id, moved)));
+		assert!(NftMaker::is_locked(&collection_id, &item_id));
+		assert!(!NftMaker::is_locked(&collection_id, &moved));
+		assert_eq!(NextSaleId::<Test>::get(), 3);
+		assert_eq!(Marketplace::on_chain_storage_version(), StorageVersion::new(1));
 	});
 }
@@ -2,10 +2,25 @@ use codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, Default, TypeInfo, MaxEncodedLen)]
-pub struct SaleItem<AccountId, Id, AssetId, ItemId, Price> {
+pub struct SaleItem<AccountId, Id, AssetId, CollectionId, ItemId, Price> {
 	pub(super) owner: AccountId,
 	pub(super) id: Id,
+	pub(super) collection_id: CollectionId,
 	pub(super) item_id: ItemId,
 	pub(super) asset_id: AssetId,
 	pub(super) price: Price,
 }
+
+#[derive(Clone, Encode, Decode, Eq, PartialEq, TypeInfo, MaxEncodedLen)]
+pub struct RentalListing<AccountId, CollectionId, ItemId, AssetId, Price, BlockNumber> {
+	pub(super) owner: AccountId,
+	pub(super) collection_id: CollectionId,
+	pub(super) item_id: ItemId,
+	pub(super) asset_id: AssetId,
+	/// Price of one period
+	pub(super) price: Price,
+	/// Length of a period in blocks
+	pub(super) period: BlockNumber,
+	/// Most periods the item can be rented for at once
+	pub(super) max_periods: u32,
+}
@@ -0,0 +1,390 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+pub mod migrations;
+#[cfg(test)]
+mod mock;
+mod price_feed;
+pub use price_feed::PRICE_FEED_URL_KEY;
+mod price_history;
+#[cfg(test)]
+mod tests;
+pub mod types;
+
+use sp_runtime::KeyTypeId;
+
+/// Key type of the accounts the offchain worker signs `set_price` transactions with. Operators
+/// add one with `author_insertKey` and authorize its account via `authorize_pricing_oracle`.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"orcl");
+
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_core::sr25519::{Public as Sr25519Public, Signature as Sr25519Signature};
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	pub struct OracleAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for OracleAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = Sr25519Signature;
+		type GenericPublic = Sr25519Public;
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use crate::{
+		price_feed, price_history,
+		types::{AssetGroupId, OraclePermission, OracleScope, PriceDescriptor, PriceQuote},
+	};
+	use frame_support::{
+		pallet_prelude::*,
+		sp_runtime::traits::{AtLeast32BitUnsigned, Convert, Zero},
+	};
+	use frame_system::{
+		offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+		pallet_prelude::*,
+	};
+	use pallet_custom_traits::PriceProvider;
+	use sp_std::vec::Vec;
+
+	#[pallet::config]
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		type AssetId: Member + Parameter + MaxEncodedLen + Copy;
+		type Balance: Member + Parameter + MaxEncodedLen + Copy + AtLeast32BitUnsigned;
+
+		/// Keys the offchain worker signs price updates with
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// Number of blocks between two price feed queries
+		#[pallet::constant]
+		type PriceFeedInterval: Get<Self::BlockNumber>;
+
+		/// Maps the asset IDs reported by the price feed to on-chain asset IDs
+		type PriceFeedAssetId: Convert<u64, Self::AssetId>;
+
+		/// Number of past prices kept per asset
+		#[pallet::constant]
+		type MaxPriceHistory: Get<u32>;
+
+		/// Number of blocks after which a price is stale and no longer returned by the getters
+		#[pallet::constant]
+		type MaxPriceAge: Get<Self::BlockNumber>;
+
+		/// Max length of the quote currency symbol of a price, e.g. `USD`
+		#[pallet::constant]
+		type QuoteSymbolLimit: Get<u32>;
+
+		/// Max number of scopes a single oracle can be authorized for
+		#[pallet::constant]
+		type MaxOracleScopes: Get<u32>;
+
+		/// Descriptor given to a token whose first price is set before root set one for it
+		#[pallet::constant]
+		type DefaultPriceDescriptor: Get<PriceDescriptor<Self::QuoteSymbolLimit>>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		// (token ID, price, block)
+		PriceSet(T::AssetId, T::Balance, T::BlockNumber),
+		// (account, scope, expiry block)
+		PriceOraclePermissionSet(T::AccountId, OracleScope<T::AssetId>, Option<T::BlockNumber>),
+		// (account, scope)
+		PriceOraclePermissionRevoked(T::AccountId, OracleScope<T::AssetId>),
+		// (token ID, group ID)
+		AssetGroupSet(T::AssetId, Option<AssetGroupId>),
+		// (token ID, quote currency, decimals)
+		PriceDescriptorSet(T::AssetId, BoundedVec<u8, T::QuoteSymbolLimit>, u8),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		NotAuthorized,
+		TooManyOracleScopes,
+		PermissionNotFound,
+	}
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	// The latest price of each token and the block it was set at, in the quote currency and
+	// decimals of its `PriceDescriptors` entry. Unlike the AMM swap price, which is relative to the
+	// other token of a pool, it's a real-world value, e.g. to spot arbitrage opportunities.
+	// `fresh_price` ignores it once it's older than `MaxPriceAge`.
+	#[pallet::storage]
+	#[pallet::unbounded]
+	pub(super) type Price<T: Config> =
+		StorageMap<_, Twox128, T::AssetId, (T::Balance, T::BlockNumber), OptionQuery>;
+
+	// The most recent prices per token, oldest first. Once `MaxPriceHistory` prices are stored,
+	// setting a new price drops the oldest one.
+	#[pallet::storage]
+	pub(super) type PriceHistory<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		BoundedVec<(T::Balance, T::BlockNumber), T::MaxPriceHistory>,
+		ValueQuery,
+	>;
+
+	// The quote currency and decimals every price of a token is expressed in. Tokens priced before
+	// root set one get `DefaultPriceDescriptor`.
+	#[pallet::storage]
+	pub(super) type PriceDescriptors<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		PriceDescriptor<T::QuoteSymbolLimit>,
+		OptionQuery,
+	>;
+
+	// The scopes an oracle account may set prices for. An account without an entry, or whose
+	// permissions have all expired, can't set prices.
+	#[pallet::storage]
+	pub(super) type PriceOracle<T: Config> = StorageMap<
+		_,
+		Twox128,
+		T::AccountId,
+		BoundedVec<OraclePermission<T::AssetId, T::BlockNumber>, T::MaxOracleScopes>,
+		OptionQuery,
+	>;
+
+	// Groups tokens so an oracle can be authorized for all of them at once, e.g. every LP token
+	#[pallet::storage]
+	pub(super) type AssetGroups<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, AssetGroupId, OptionQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn offchain_worker(block_number: T::BlockNumber) {
+			let interval = T::PriceFeedInterval::get();
+			if interval.is_zero() || !(block_number % interval).is_zero() {
+				return
+			}
+
+			if let Err(e) = Self::submit_feed_prices() {
+				log::warn!(target: "oracle", "Price feed not submitted: {}", e);
+			}
+		}
+	}
+
+	impl<T: Config> PriceProvider<T::AssetId, T::Balance> for Pallet<T> {
+		fn price(asset_id: &T::AssetId) -> Option<(T::Balance, Vec<u8>, u8)> {
+			let (price, _) = Self::fresh_price(*asset_id)?;
+			let descriptor = Self::price_descriptor(*asset_id);
+			Some((price, descriptor.quote.into_inner(), descriptor.decimals))
+		}
+
+		fn priced_assets() -> Vec<T::AssetId> {
+			PriceDescriptors::<T>::iter_keys().collect()
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether `who` holds an unexpired permission covering `asset_id`.
+		pub fn can_set_price(who: &T::AccountId, asset_id: T::AssetId) -> bool {
+			let permissions = match PriceOracle::<T>::get(who) {
+				Some(permissions) => permissions,
+				None => return false,
+			};
+			let group = AssetGroups::<T>::get(asset_id);
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			permissions.iter().filter(|permission| permission.is_active(&now)).any(|permission| {
+				match permission.scope {
+					OracleScope::AllAssets => true,
+					OracleScope::Asset(id) => id == asset_id,
+					OracleScope::Group(id) => Some(id) == group,
+				}
+			})
+		}
+
+		/// The latest price of a token and the block it was set at, or `None` when there is no
+		/// price or it is older than `MaxPriceAge`.
+		pub fn fresh_price(asset_id: T::AssetId) -> Option<(T::Balance, T::BlockNumber)> {
+			let (price, updated_at) = Price::<T>::get(asset_id)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			if price_history::is_stale(updated_at, now, T::MaxPriceAge::get()) {
+				return None
+			}
+
+			Some((price, updated_at))
+		}
+
+		/// The latest fresh price of a token with its quote currency and the range of the fresh
+		/// prices in its history. `None` when the price is stale.
+		pub fn price_quote(
+			asset_id: T::AssetId,
+		) -> Option<PriceQuote<T::Balance, T::BlockNumber, PriceDescriptor<T::QuoteSymbolLimit>>> {
+			let (price, updated_at) = Self::fresh_price(asset_id)?;
+			let descriptor = Self::price_descriptor(asset_id);
+			let now = <frame_system::Pallet<T>>::block_number();
+			let history = PriceHistory::<T>::get(asset_id);
+			let (low, high, samples) =
+				price_history::fresh_range(&history, now, T::MaxPriceAge::get())
+					.unwrap_or((price, price, 1));
+
+			Some(PriceQuote { price, updated_at, descriptor, low, high, samples })
+		}
+
+		/// The quote currency of a token's prices, `DefaultPriceDescriptor` when none was set
+		pub fn price_descriptor(asset_id: T::AssetId) -> PriceDescriptor<T::QuoteSymbolLimit> {
+			PriceDescriptors::<T>::get(asset_id).unwrap_or_else(T::DefaultPriceDescriptor::get)
+		}
+
+		/// Give a token `DefaultPriceDescriptor`
+		pub(crate) fn set_default_descriptor(asset_id: T::AssetId) {
+			let descriptor = T::DefaultPriceDescriptor::get();
+			Self::deposit_event(Event::PriceDescriptorSet(
+				asset_id,
+				descriptor.quote.clone(),
+				descriptor.decimals,
+			));
+			PriceDescriptors::<T>::insert(asset_id, descriptor);
+		}
+
+		/// Every stored price of a token, oldest first, stale ones included.
+		pub fn price_history(asset_id: T::AssetId) -> Vec<(T::Balance, T::BlockNumber)> {
+			PriceHistory::<T>::get(asset_id).into_inner()
+		}
+
+		/// Fetch prices from the configured feed and submit each one as a signed `set_price`
+		/// transaction from the local oracle key.
+		fn submit_feed_prices() -> Result<(), &'static str> {
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if !signer.can_sign() {
+				return Err("no oracle key in the keystore")
+			}
+
+			let url = price_feed::endpoint().ok_or("no price feed URL set")?;
+			let prices = price_feed::fetch_prices(&url).map_err(|_| "unable to fetch prices")?;
+
+			for (feed_asset_id, feed_price) in prices {
+				let asset_id = T::PriceFeedAssetId::convert(feed_asset_id);
+				let price = match T::Balance::try_from(feed_price) {
+					Ok(price) => price,
+					Err(_) => continue,
+				};
+
+				match signer.send_signed_transaction(|_| Call::set_price { asset_id, price }) {
+					Some((_, Ok(()))) => {},
+					Some((_, Err(()))) => return Err("unable to submit price transaction"),
+					None => return Err("no oracle account to sign with"),
+				}
+			}
+
+			Ok(())
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Allow `who` to set prices for the tokens in `scope` until `expires_at`, or forever when
+		/// no expiry is given. Replaces an existing permission for the same scope.
+		#[pallet::weight((1_000_000, Pays::Yes))]
+		pub fn authorize_pricing_oracle(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			scope: OracleScope<T::AssetId>,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			PriceOracle::<T>::try_mutate(&who, |maybe_permissions| -> DispatchResult {
+				let permissions = maybe_permissions.get_or_insert_with(Default::default);
+				permissions.retain(|permission| permission.scope != scope);
+				permissions
+					.try_push(OraclePermission { scope, expires_at })
+					.map_err(|_| Error::<T>::TooManyOracleScopes)?;
+				Ok(())
+			})?;
+			Self::deposit_event(Event::PriceOraclePermissionSet(who, scope, expires_at));
+			Ok(Pays::No.into())
+		}
+
+		/// Remove the permission of `who` for `scope`. Other scopes of `who` are kept.
+		#[pallet::weight((1_000_000, Pays::Yes))]
+		pub fn revoke_pricing_oracle(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			scope: OracleScope<T::AssetId>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			PriceOracle::<T>::try_mutate_exists(&who, |maybe_permissions| -> DispatchResult {
+				let permissions =
+					maybe_permissions.as_mut().ok_or(Error::<T>::PermissionNotFound)?;
+				let count = permissions.len();
+				permissions.retain(|permission| permission.scope != scope);
+				ensure!(permissions.len() < count, Error::<T>::PermissionNotFound);
+
+				if permissions.is_empty() {
+					*maybe_permissions = None;
+				}
+				Ok(())
+			})?;
+			Self::deposit_event(Event::PriceOraclePermissionRevoked(who, scope));
+			Ok(Pays::No.into())
+		}
+
+		/// Add a token to an asset group, or remove it from its group with `None`
+		#[pallet::weight((1_000_000, Pays::Yes))]
+		pub fn set_asset_group(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			group: Option<AssetGroupId>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			AssetGroups::<T>::set(asset_id, group);
+			Self::deposit_event(Event::AssetGroupSet(asset_id, group));
+			Ok(Pays::No.into())
+		}
+
+		#[pallet::weight((1_000_000, Pays::Yes))]
+		pub fn set_price(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			price: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::can_set_price(&sender, asset_id), Error::<T>::NotAuthorized);
+			if !PriceDescriptors::<T>::contains_key(asset_id) {
+				Self::set_default_descriptor(asset_id);
+			}
+
+			let current_block = <frame_system::Pallet<T>>::block_number();
+			Price::<T>::insert(asset_id, (price, current_block));
+			PriceHistory::<T>::mutate(asset_id, |history| {
+				price_history::push(history, (price, current_block))
+			});
+			Self::deposit_event(Event::PriceSet(asset_id, price, current_block));
+
+			Ok(Pays::No.into())
+		}
+
+		#[pallet::weight((1_000_000, Pays::No))]
+		pub fn set_price_descriptor(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			quote: BoundedVec<u8, T::QuoteSymbolLimit>,
+			decimals: u8,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			PriceDescriptors::<T>::insert(
+				asset_id,
+				PriceDescriptor { quote: quote.clone(), decimals },
+			);
+			Self::deposit_event(Event::PriceDescriptorSet(asset_id, quote, decimals));
+			Ok(())
+		}
+	}
+}
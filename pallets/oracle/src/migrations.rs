@@ -0,0 +1,68 @@
+use crate::{
+	types::{OraclePermission, OracleScope},
+	Config, Pallet, PriceOracle,
+};
+use frame_support::{
+	storage::migration::{move_storage_from_pallet, storage_iter},
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+	weights::Weight,
+	BoundedVec,
+};
+use sp_std::{marker::PhantomData, vec};
+
+/// Storage items that lived in the DEX pallet before the oracle got its own pallet
+const MOVED_STORAGE: [&[u8]; 5] =
+	[b"Price", b"PriceHistory", b"PriceDescriptors", b"PriceOracle", b"AssetGroups"];
+
+/// Moves the prices, price descriptors and oracle permissions out of the DEX pallet `Dex`.
+///
+/// Before v1 of the DEX pallet, `PriceOracle` was an `AccountId -> bool` whitelist. When the
+/// moved data is that old, accounts that were set to `true` keep the right to price every token,
+/// with no expiry, and accounts that were set to `false` are removed, since they never should
+/// have been able to set prices.
+///
+/// Prices don't need a descriptor to be moved: tokens without one are quoted in
+/// `DefaultPriceDescriptor`.
+pub struct MigrateFromDex<T, Dex>(PhantomData<(T, Dex)>);
+
+impl<T: Config, Dex: GetStorageVersion + PalletInfoAccess> OnRuntimeUpgrade
+	for MigrateFromDex<T, Dex>
+{
+	fn on_runtime_upgrade() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 0 {
+			log::info!(target: "oracle", "Oracle storage already migrated, skipping");
+			return T::DbWeight::get().reads(1)
+		}
+
+		let dex = Dex::name();
+		let oracle = <Pallet<T> as PalletInfoAccess>::name();
+		// `Price` and `PriceOracle` keys are hashed with `Twox128`, so they are counted as raw keys
+		let permissions = storage_iter::<()>(dex.as_bytes(), b"PriceOracle").count() as u64;
+		let mut moved = 0;
+		for storage in MOVED_STORAGE {
+			moved += storage_iter::<()>(dex.as_bytes(), storage).count() as u64;
+			move_storage_from_pallet(storage, dex.as_bytes(), oracle.as_bytes());
+		}
+		log::info!(target: "oracle", "Moved {} oracle keys from {} to {}", moved, dex, oracle);
+		// Both storage versions, a read to count each key, and a read and two writes to move it
+		let mut weight = T::DbWeight::get().reads_writes(2 + permissions + moved * 2, moved * 2);
+
+		if Dex::on_chain_storage_version() < 1 {
+			PriceOracle::<T>::translate_values::<bool, _>(|is_permissioned| {
+				if !is_permissioned {
+					return None
+				}
+				BoundedVec::try_from(vec![OraclePermission {
+					scope: OracleScope::AllAssets,
+					expires_at: None,
+				}])
+				.ok()
+			});
+			log::info!(target: "oracle", "Migrated {} oracle permissions", permissions);
+			weight += T::DbWeight::get().reads_writes(permissions, permissions);
+		}
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+		weight + T::DbWeight::get().writes(1)
+	}
+}
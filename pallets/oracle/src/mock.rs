@@ -0,0 +1,149 @@
+use crate as pallet_oracle;
+use frame_support::{
+	parameter_types,
+	traits::{
+		ConstU16, ConstU32, ConstU64, CrateVersion, GetStorageVersion, PalletInfoAccess,
+		StorageVersion,
+	},
+};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestXt},
+	traits::{
+		BlakeTwo256, ConvertInto, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify,
+	},
+	AccountId32, MultiSignature,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+type Extrinsic = TestXt<Call, ()>;
+type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Oracle: pallet_oracle::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl system::offchain::SigningTypes for Test {
+	type Public = <MultiSignature as Verify>::Signer;
+	type Signature = MultiSignature;
+}
+
+impl<LocalCall> system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	fn create_transaction<C: system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		_public: <MultiSignature as Verify>::Signer,
+		_account: AccountId,
+		nonce: u64,
+	) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
+parameter_types! {
+	pub DefaultPriceDescriptor: crate::types::PriceDescriptor<ConstU32<8>> =
+		crate::types::PriceDescriptor { quote: b"USD".to_vec().try_into().unwrap(), decimals: 6 };
+}
+
+impl pallet_oracle::Config for Test {
+	type Event = Event;
+	type AssetId = u64;
+	type Balance = u64;
+	type AuthorityId = pallet_oracle::crypto::OracleAuthId;
+	type PriceFeedInterval = ConstU64<10>;
+	type PriceFeedAssetId = ConvertInto;
+	type MaxPriceHistory = ConstU32<3>;
+	type MaxPriceAge = ConstU64<10>;
+	type QuoteSymbolLimit = ConstU32<8>;
+	type MaxOracleScopes = ConstU32<2>;
+	type DefaultPriceDescriptor = DefaultPriceDescriptor;
+}
+
+/// The DEX pallet the oracle storage is migrated from
+pub struct LegacyDex;
+
+impl PalletInfoAccess for LegacyDex {
+	fn index() -> usize {
+		2
+	}
+
+	fn name() -> &'static str {
+		"Dex"
+	}
+
+	fn module_name() -> &'static str {
+		"pallet_template"
+	}
+
+	fn crate_version() -> CrateVersion {
+		CrateVersion::new(4, 0, 0)
+	}
+}
+
+impl GetStorageVersion for LegacyDex {
+	fn current_storage_version() -> StorageVersion {
+		StorageVersion::new(1)
+	}
+
+	fn on_chain_storage_version() -> StorageVersion {
+		StorageVersion::get::<Self>()
+	}
+}
+
+pub const ALICE: AccountId32 = AccountId32::new([1; 32]);
+pub const BOB: AccountId32 = AccountId32::new([2; 32]);
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,158 @@
+use lite_json::{json_parser::parse_json, JsonValue};
+use sp_core::offchain::StorageKind;
+use sp_runtime::offchain::{http, Duration};
+use sp_std::{str, vec::Vec};
+
+/// Persistent local storage key of the price feed the offchain worker queries. The node sets it
+/// from `--price-feed-url`, and operators can change it with `offchain_localStorageSet`.
+pub const PRICE_FEED_URL_KEY: &[u8] = b"oracle::price-feed-url";
+
+const FETCH_TIMEOUT_MILLIS: u64 = 2_000;
+
+#[derive(Debug)]
+pub enum Errors {
+	Http(http::Error),
+	InvalidUtf8,
+	InvalidJson,
+}
+
+impl PartialEq for Errors {
+	fn eq(&self, other: &Self) -> bool {
+		core::mem::discriminant(self) == core::mem::discriminant(other)
+	}
+}
+
+/// Returns the endpoint set in the node's local storage, if any.
+pub fn endpoint() -> Option<Vec<u8>> {
+	sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, PRICE_FEED_URL_KEY)
+}
+
+/// Fetches `(feed asset ID, price)` pairs from `url`.
+pub fn fetch_prices(url: &[u8]) -> Result<Vec<(u64, u128)>, Errors> {
+	let url = str::from_utf8(url).map_err(|_| Errors::InvalidUtf8)?;
+	let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT_MILLIS));
+	let pending = http::Request::get(url)
+		.deadline(deadline)
+		.send()
+		.map_err(|_| Errors::Http(http::Error::IoError))?;
+	let response = pending
+		.try_wait(deadline)
+		.map_err(|_| Errors::Http(http::Error::DeadlineReached))?
+		.map_err(Errors::Http)?;
+	if response.code != 200 {
+		return Err(Errors::Http(http::Error::Unknown))
+	}
+
+	let body = response.body().collect::<Vec<u8>>();
+	let body = str::from_utf8(&body).map_err(|_| Errors::InvalidUtf8)?;
+	parse_prices(body)
+}
+
+/// Parses a feed of the form `{"<asset ID>": <price>, ...}`. Prices are integers in the quote
+/// currency of the asset's price descriptor, scaled by its decimals, e.g. `1250000` is 1.25 USD
+/// with 6 decimals. They may be quoted as strings when they don't fit in a JSON number.
+/// Fractional prices are rejected rather than truncated.
+pub fn parse_prices(body: &str) -> Result<Vec<(u64, u128)>, Errors> {
+	let entries = match parse_json(body) {
+		Ok(JsonValue::Object(entries)) => entries,
+		_ => return Err(Errors::InvalidJson),
+	};
+
+	let mut prices = Vec::with_capacity(entries.len());
+	for (key, value) in entries {
+		let asset_id = parse_integer(&key)
+			.and_then(|id| u64::try_from(id).ok())
+			.ok_or(Errors::InvalidJson)?;
+		let price = match value {
+			JsonValue::Number(number)
+				if !number.negative && number.exponent == 0 && number.fraction == 0 =>
+				number.integer as u128,
+			JsonValue::String(chars) => parse_integer(&chars).ok_or(Errors::InvalidJson)?,
+			_ => return Err(Errors::InvalidJson),
+		};
+		prices.push((asset_id, price));
+	}
+
+	Ok(prices)
+}
+
+fn parse_integer(chars: &[char]) -> Option<u128> {
+	if chars.is_empty() {
+		return None
+	}
+	chars.iter().try_fold(0u128, |acc, c| {
+		let digit = c.to_digit(10)?;
+		acc.checked_mul(10)?.checked_add(digit.into())
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::offchain::{testing, OffchainWorkerExt};
+
+	#[test]
+	fn test_parse_prices() {
+		let body = r#"{"1": 1250000000000, "4": "33000000000000000000000"}"#;
+
+		let prices = parse_prices(body).unwrap();
+
+		assert_eq!(prices, vec![(1, 1_250_000_000_000), (4, 33_000_000_000_000_000_000_000)]);
+	}
+
+	#[test]
+	fn test_parse_prices_rejects_malformed_feeds() {
+		assert_eq!(parse_prices(r#"[1, 2]"#).err().unwrap(), Errors::InvalidJson);
+		assert_eq!(parse_prices(r#"{"PIPS": 1}"#).err().unwrap(), Errors::InvalidJson);
+		assert_eq!(parse_prices(r#"{"1": -5}"#).err().unwrap(), Errors::InvalidJson);
+		assert_eq!(parse_prices(r#"{"1": "1.5"}"#).err().unwrap(), Errors::InvalidJson);
+	}
+
+	#[test]
+	fn test_parse_prices_rejects_numeric_fractions() {
+		assert_eq!(parse_prices(r#"{"1": 1.5}"#).err().unwrap(), Errors::InvalidJson);
+		assert_eq!(parse_prices(r#"{"1": 2.0}"#).unwrap(), vec![(1, 2)]);
+	}
+
+	#[test]
+	fn test_fetch_prices_from_stub_server() {
+		let url = "http://localhost:8000/prices";
+		let (offchain, state) = testing::TestOffchainExt::new();
+		let mut t = sp_io::TestExternalities::default();
+		t.register_extension(OffchainWorkerExt::new(offchain));
+		state.write().expect_request(testing::PendingRequest {
+			method: "GET".into(),
+			uri: url.into(),
+			response: Some(br#"{"2": 700000000000}"#.to_vec()),
+			sent: true,
+			..Default::default()
+		});
+
+		t.execute_with(|| {
+			let prices = fetch_prices(url.as_bytes()).unwrap();
+
+			assert_eq!(prices, vec![(2, 700_000_000_000)]);
+		});
+	}
+
+	#[test]
+	fn test_fetch_prices_rejects_non_json_responses() {
+		let url = "http://localhost:8000/prices";
+		let (offchain, state) = testing::TestOffchainExt::new();
+		let mut t = sp_io::TestExternalities::default();
+		t.register_extension(OffchainWorkerExt::new(offchain));
+		state.write().expect_request(testing::PendingRequest {
+			method: "GET".into(),
+			uri: url.into(),
+			response: Some(b"Internal Server Error".to_vec()),
+			sent: true,
+			..Default::default()
+		});
+
+		t.execute_with(|| {
+			let err = fetch_prices(url.as_bytes()).err().unwrap();
+
+			assert_eq!(err, Errors::InvalidJson);
+		});
+	}
+}
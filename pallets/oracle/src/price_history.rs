@@ -0,0 +1,75 @@
+use frame_support::{traits::Get, BoundedVec};
+use sp_runtime::traits::Saturating;
+
+/// Appends `entry`, dropping the oldest entry first when the history is full.
+pub fn push<T, S: Get<u32>>(history: &mut BoundedVec<T, S>, entry: T) {
+	if S::get() == 0 {
+		return
+	}
+	if history.len() as u32 >= S::get() {
+		history.remove(0);
+	}
+	// The oldest entry was removed above, so there is always room
+	let _ = history.try_push(entry);
+}
+
+/// A price set at `updated_at` is stale once more than `max_age` blocks have passed.
+pub fn is_stale<B: Saturating + PartialOrd + Copy>(updated_at: B, now: B, max_age: B) -> bool {
+	now.saturating_sub(updated_at) > max_age
+}
+
+/// Returns (lowest price, highest price, number of samples) over the entries of `history` that
+/// are not stale, or `None` when every entry is stale.
+pub fn fresh_range<P: Ord + Copy, B: Saturating + PartialOrd + Copy>(
+	history: &[(P, B)],
+	now: B,
+	max_age: B,
+) -> Option<(P, P, u32)> {
+	history.iter().filter(|(_, block)| !is_stale(*block, now, max_age)).fold(
+		None,
+		|range, (price, _)| match range {
+			None => Some((*price, *price, 1)),
+			Some((low, high, samples)) => Some((low.min(*price), high.max(*price), samples + 1)),
+		},
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::traits::ConstU32;
+
+	#[test]
+	fn test_push_drops_oldest_entry_when_full() {
+		let mut history = BoundedVec::<u32, ConstU32<3>>::default();
+
+		for price in 1..=5 {
+			push(&mut history, price);
+		}
+
+		assert_eq!(history.into_inner(), vec![3, 4, 5]);
+	}
+
+	#[test]
+	fn test_is_stale() {
+		assert!(!is_stale(10u32, 20u32, 10u32));
+		assert!(is_stale(10u32, 21u32, 10u32));
+		assert!(!is_stale(10u32, 5u32, 10u32));
+	}
+
+	#[test]
+	fn test_fresh_range_ignores_stale_entries() {
+		let history: Vec<(u128, u32)> = vec![(50, 1), (100, 15), (90, 18), (120, 20)];
+
+		let range = fresh_range(&history, 25, 10);
+
+		assert_eq!(range, Some((90, 120, 3)));
+	}
+
+	#[test]
+	fn test_fresh_range_is_none_when_all_entries_are_stale() {
+		let history: Vec<(u128, u32)> = vec![(50, 1), (100, 2)];
+
+		assert_eq!(fresh_range(&history, 25, 10), None);
+	}
+}
@@ -0,0 +1,155 @@
+use crate::{
+	migrations::MigrateFromDex,
+	mock::*,
+	types::{OraclePermission, OracleScope},
+	Error, Price, PriceOracle,
+};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::migration::{put_storage_value, storage_iter},
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	StorageHasher, Twox128,
+};
+use pallet_custom_traits::PriceProvider;
+use sp_runtime::DispatchError;
+
+const DOT: u64 = 1;
+const KSM: u64 = 2;
+const LP_TOKENS: u32 = 7;
+
+fn authorize(who: &AccountId32, scope: OracleScope<u64>, expires_at: Option<u64>) {
+	assert_ok!(Oracle::authorize_pricing_oracle(Origin::root(), who.clone(), scope, expires_at));
+}
+
+#[test]
+fn oracles_can_only_price_assets_in_their_scope() {
+	new_test_ext().execute_with(|| {
+		authorize(&ALICE, OracleScope::Asset(DOT), None);
+
+		assert_ok!(Oracle::set_price(Origin::signed(ALICE), DOT, 5_000_000));
+		assert_eq!(Oracle::fresh_price(DOT), Some((5_000_000, 1)));
+		assert_noop!(
+			Oracle::set_price(Origin::signed(ALICE), KSM, 5_000_000),
+			Error::<Test>::NotAuthorized
+		);
+		assert_noop!(
+			Oracle::set_price(Origin::signed(BOB), DOT, 5_000_000),
+			Error::<Test>::NotAuthorized
+		);
+	});
+}
+
+#[test]
+fn group_scopes_cover_the_assets_of_the_group() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Oracle::set_asset_group(Origin::root(), KSM, Some(LP_TOKENS)));
+		authorize(&ALICE, OracleScope::Group(LP_TOKENS), None);
+
+		assert!(Oracle::can_set_price(&ALICE, KSM));
+		assert!(!Oracle::can_set_price(&ALICE, DOT));
+
+		assert_ok!(Oracle::set_asset_group(Origin::root(), KSM, None));
+		assert!(!Oracle::can_set_price(&ALICE, KSM));
+	});
+}
+
+#[test]
+fn expired_permissions_are_rejected() {
+	new_test_ext().execute_with(|| {
+		authorize(&ALICE, OracleScope::AllAssets, Some(5));
+
+		System::set_block_number(4);
+		assert_ok!(Oracle::set_price(Origin::signed(ALICE), DOT, 1));
+		System::set_block_number(5);
+		assert_noop!(
+			Oracle::set_price(Origin::signed(ALICE), DOT, 2),
+			Error::<Test>::NotAuthorized
+		);
+	});
+}
+
+#[test]
+fn revoking_a_scope_keeps_the_other_scopes() {
+	new_test_ext().execute_with(|| {
+		authorize(&ALICE, OracleScope::Asset(DOT), None);
+		authorize(&ALICE, OracleScope::Asset(KSM), None);
+
+		assert_ok!(Oracle::revoke_pricing_oracle(Origin::root(), ALICE, OracleScope::Asset(DOT)));
+
+		assert!(!Oracle::can_set_price(&ALICE, DOT));
+		assert!(Oracle::can_set_price(&ALICE, KSM));
+		assert_noop!(
+			Oracle::revoke_pricing_oracle(Origin::root(), ALICE, OracleScope::Asset(DOT)),
+			Error::<Test>::PermissionNotFound
+		);
+
+		assert_ok!(Oracle::revoke_pricing_oracle(Origin::root(), ALICE, OracleScope::Asset(KSM)));
+		assert!(!PriceOracle::<Test>::contains_key(ALICE));
+	});
+}
+
+#[test]
+fn only_root_manages_permissions_within_the_scope_limit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Oracle::authorize_pricing_oracle(
+				Origin::signed(ALICE),
+				ALICE,
+				OracleScope::AllAssets,
+				None
+			),
+			DispatchError::BadOrigin
+		);
+
+		authorize(&ALICE, OracleScope::Asset(DOT), None);
+		authorize(&ALICE, OracleScope::Asset(KSM), None);
+		// Replacing a scope doesn't take another slot
+		authorize(&ALICE, OracleScope::Asset(KSM), Some(10));
+		assert_noop!(
+			Oracle::authorize_pricing_oracle(Origin::root(), ALICE, OracleScope::AllAssets, None),
+			Error::<Test>::TooManyOracleScopes
+		);
+	});
+}
+
+#[test]
+fn migration_turns_the_dex_whitelist_into_permissions() {
+	new_test_ext().execute_with(|| {
+		put_storage_value(b"Dex", b"PriceOracle", &Twox128::hash(&ALICE.encode()), true);
+		put_storage_value(b"Dex", b"PriceOracle", &Twox128::hash(&BOB.encode()), false);
+		put_storage_value(b"Dex", b"Price", &Twox128::hash(&DOT.encode()), (5_000_000u64, 1u64));
+
+		MigrateFromDex::<Test, LegacyDex>::on_runtime_upgrade();
+
+		let permission = OraclePermission { scope: OracleScope::AllAssets, expires_at: None };
+		assert_eq!(PriceOracle::<Test>::get(ALICE).unwrap().into_inner(), vec![permission]);
+		assert!(!PriceOracle::<Test>::contains_key(BOB));
+		assert_eq!(Price::<Test>::get(DOT), Some((5_000_000, 1)));
+		assert_eq!(
+			<Oracle as PriceProvider<_, _>>::price(&DOT),
+			Some((5_000_000, b"USD".to_vec(), 6))
+		);
+		assert_eq!(storage_iter::<()>(b"Dex", b"PriceOracle").count(), 0);
+		assert_eq!(storage_iter::<()>(b"Dex", b"Price").count(), 0);
+		assert_eq!(Oracle::on_chain_storage_version(), StorageVersion::new(1));
+	});
+}
+
+#[test]
+fn migration_keeps_the_permissions_of_a_v1_dex_and_runs_once() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<LegacyDex>();
+		let permission =
+			OraclePermission { scope: OracleScope::Asset(DOT), expires_at: Some(5u64) };
+		let key = Twox128::hash(&ALICE.encode());
+		put_storage_value(b"Dex", b"PriceOracle", &key, vec![permission.clone()]);
+
+		MigrateFromDex::<Test, LegacyDex>::on_runtime_upgrade();
+		put_storage_value(b"Dex", b"PriceOracle", &key, true);
+		MigrateFromDex::<Test, LegacyDex>::on_runtime_upgrade();
+
+		assert_eq!(PriceOracle::<Test>::get(ALICE).unwrap().into_inner(), vec![permission]);
+		assert_eq!(storage_iter::<()>(b"Dex", b"PriceOracle").count(), 1);
+	});
+}
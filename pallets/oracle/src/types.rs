@@ -0,0 +1,64 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	traits::Get, BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebug,
+	RuntimeDebugNoBound,
+};
+use scale_info::TypeInfo;
+
+/// What an asset's oracle price is denominated in, e.g. `USD` with 6 decimals
+#[derive(
+	CloneNoBound,
+	Encode,
+	Decode,
+	EqNoBound,
+	PartialEqNoBound,
+	RuntimeDebugNoBound,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(SymbolLimit))]
+pub struct PriceDescriptor<SymbolLimit: Get<u32>> {
+	pub quote: BoundedVec<u8, SymbolLimit>,
+	pub decimals: u8,
+}
+
+/// A fresh oracle price together with how much the recent history agrees with it
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct PriceQuote<Balance, BlockNumber, Descriptor> {
+	pub price: Balance,
+	pub updated_at: BlockNumber,
+	pub descriptor: Descriptor,
+	/// Lowest fresh price in the history
+	pub low: Balance,
+	/// Highest fresh price in the history
+	pub high: Balance,
+	/// Number of fresh prices in the history
+	pub samples: u32,
+}
+
+/// Identifier of a group of tokens an oracle can be authorized for at once
+pub type AssetGroupId = u32;
+
+/// The tokens an oracle permission covers
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum OracleScope<AssetId> {
+	AllAssets,
+	Asset(AssetId),
+	Group(AssetGroupId),
+}
+
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct OraclePermission<AssetId, BlockNumber> {
+	pub scope: OracleScope<AssetId>,
+	/// The permission no longer applies from this block on
+	pub expires_at: Option<BlockNumber>,
+}
+
+impl<AssetId, BlockNumber: PartialOrd> OraclePermission<AssetId, BlockNumber> {
+	pub fn is_active(&self, now: &BlockNumber) -> bool {
+		match &self.expires_at {
+			Some(expires_at) => now < expires_at,
+			None => true,
+		}
+	}
+}
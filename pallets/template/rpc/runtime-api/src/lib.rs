@@ -0,0 +1,22 @@
+//! Runtime API definition for the USD valuation of Dex assets.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	pub trait ValuationApi<AccountId, AssetId, Balance> where
+		AccountId: Codec,
+		AssetId: Codec,
+		Balance: Codec,
+	{
+		/// USD value of `amount` of `asset_id`, or `None` when it can't be priced
+		fn usd_value(asset_id: AssetId, amount: Balance) -> Option<Balance>;
+
+		/// USD value of `amount` of the LP token `lp_id`, or `None` when it can't be priced
+		fn lp_usd_value(lp_id: AssetId, amount: Balance) -> Option<Balance>;
+
+		/// Total USD value of the assets `who` holds
+		fn portfolio_usd_value(who: AccountId) -> Balance;
+	}
+}
@@ -0,0 +1,166 @@
+//! RPC interface for the USD valuation of Dex assets.
+
+use std::{convert::TryInto, fmt::Debug, marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+pub use pallet_template_rpc_runtime_api::ValuationApi as ValuationRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, MaybeDisplay},
+};
+
+#[rpc(client, server)]
+pub trait ValuationApi<BlockHash, AccountId, AssetId> {
+	/// USD value of `amount` of `asset_id`
+	#[method(name = "dex_usdValue")]
+	fn usd_value(
+		&self,
+		asset_id: AssetId,
+		amount: NumberOrHex,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<NumberOrHex>>;
+
+	/// USD value of `amount` of the LP token `lp_id`
+	#[method(name = "dex_lpUsdValue")]
+	fn lp_usd_value(
+		&self,
+		lp_id: AssetId,
+		amount: NumberOrHex,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<NumberOrHex>>;
+
+	/// Total USD value of the assets `who` holds
+	#[method(name = "dex_portfolioUsdValue")]
+	fn portfolio_usd_value(&self, who: AccountId, at: Option<BlockHash>)
+		-> RpcResult<NumberOrHex>;
+}
+
+/// Provides RPC methods to query the USD value of assets.
+pub struct Valuation<C, Block, Balance> {
+	client: Arc<C>,
+	_marker: PhantomData<(Block, Balance)>,
+}
+
+impl<C, Block, Balance> Valuation<C, Block, Balance> {
+	/// Creates a new instance of the Valuation RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error codes of the Valuation RPC
+pub enum Error {
+	/// The call to the runtime failed
+	RuntimeError,
+	/// The amount doesn't fit in the runtime's balance type
+	InvalidAmount,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::InvalidAmount => 2,
+		}
+	}
+}
+
+fn runtime_error(e: impl Debug) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query the valuation",
+		Some(format!("{:?}", e)),
+	))
+	.into()
+}
+
+fn invalid_amount(amount: NumberOrHex) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		Error::InvalidAmount.into(),
+		"Amount doesn't fit in the balance type",
+		Some(format!("{:?}", amount)),
+	))
+	.into()
+}
+
+fn into_rpc_balance<Balance: MaybeDisplay + Copy + TryInto<NumberOrHex>>(
+	value: Balance,
+) -> RpcResult<NumberOrHex> {
+	value.try_into().map_err(|_| {
+		CallError::Custom(ErrorObject::owned(
+			Error::RuntimeError.into(),
+			format!("{} doesn't fit in NumberOrHex representation", value),
+			None::<()>,
+		))
+		.into()
+	})
+}
+
+impl<C, Block, AccountId, AssetId, Balance>
+	ValuationApiServer<<Block as BlockT>::Hash, AccountId, AssetId> for Valuation<C, Block, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ValuationRuntimeApi<Block, AccountId, AssetId, Balance>,
+	AccountId: Codec,
+	AssetId: Codec,
+	Balance: Codec
+		+ MaybeDisplay
+		+ Copy
+		+ TryInto<NumberOrHex>
+		+ TryFrom<NumberOrHex>
+		+ Send
+		+ Sync
+		+ 'static,
+{
+	fn usd_value(
+		&self,
+		asset_id: AssetId,
+		amount: NumberOrHex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let amount: Balance = amount.try_into().map_err(|_| invalid_amount(amount))?;
+
+		api.usd_value(&at, asset_id, amount)
+			.map_err(runtime_error)?
+			.map(into_rpc_balance)
+			.transpose()
+	}
+
+	fn lp_usd_value(
+		&self,
+		lp_id: AssetId,
+		amount: NumberOrHex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let amount: Balance = amount.try_into().map_err(|_| invalid_amount(amount))?;
+
+		api.lp_usd_value(&at, lp_id, amount)
+			.map_err(runtime_error)?
+			.map(into_rpc_balance)
+			.transpose()
+	}
+
+	fn portfolio_usd_value(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<NumberOrHex> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		into_rpc_balance(api.portfolio_usd_value(&at, who).map_err(runtime_error)?)
+	}
+}
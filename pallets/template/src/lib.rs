@@ -2,17 +2,27 @@
 
 pub use pallet::*;
 mod dex_pricer;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod valuation;
 
 #[frame_support::pallet]
 pub mod pallet {
-	use crate::dex_pricer::{DexPricer, TokenPair};
+	use crate::{
+		dex_pricer::{DexPricer, TokenPair},
+		valuation,
+	};
 	use frame_support::{
 		pallet_prelude::*,
-		sp_runtime::traits::AccountIdConversion,
-		traits::fungibles::{Inspect, Mutate, Transfer},
+		sp_runtime::{traits::AccountIdConversion, SaturatedConversion},
+		traits::fungibles::{Inspect, InspectMetadata, Mutate, Transfer},
 		PalletId,
 	};
 	use frame_system::pallet_prelude::*;
+	use pallet_custom_traits::PriceProvider;
+	use sp_std::vec::Vec;
 
 	type AssetIdOf<T: Config> = <T::Assets as Inspect<T::AccountId>>::AssetId;
 	type BalanceOf<T: Config> = <T::Assets as Inspect<T::AccountId>>::Balance;
@@ -20,10 +30,23 @@ pub mod pallet {
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
-		type Assets: Inspect<Self::AccountId> + Transfer<Self::AccountId> + Mutate<Self::AccountId>;
+		type Assets: Inspect<Self::AccountId>
+			+ Transfer<Self::AccountId>
+			+ Mutate<Self::AccountId>
+			+ InspectMetadata<Self::AccountId>;
+
+		/// Real world prices of the assets, used by the valuation API
+		type Prices: PriceProvider<
+			<Self::Assets as Inspect<Self::AccountId>>::AssetId,
+			<Self::Assets as Inspect<Self::AccountId>>::Balance,
+		>;
 
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
+
+		/// Decimals of the USD values returned by the valuation API
+		#[pallet::constant]
+		type UsdDecimals: Get<u8>;
 	}
 
 	#[pallet::event]
@@ -37,10 +60,6 @@ pub mod pallet {
 		LiquitdityClaimed(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
 		// (Caller, pool ID, amount A, amount B)
 		TokensSwapped(T::AccountId, AssetIdOf<T>, BalanceOf<T>, BalanceOf<T>),
-		// (token ID, price, block)
-		PriceSet(AssetIdOf<T>, BalanceOf<T>, T::BlockNumber),
-		// (account, has_permission)
-		PriceOraclePermissionSet(T::AccountId, bool),
 		// (pool ID, asset A ID, asset B ID)
 		PoolCreated(AssetIdOf<T>, AssetIdOf<T>, AssetIdOf<T>),
 		// (pool ID, From Asset ID, amount)
@@ -58,22 +77,23 @@ pub mod pallet {
 		TokenNotInPool,
 		SwapExceedsFunds,
 	}
+
+	// v1 replaced the `PriceOracle` whitelist with scoped permissions. That storage now lives in
+	// the oracle pallet, but its `MigrateFromDex` reads this version to tell which layout the
+	// moved permissions have, so it must not be reset.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
-	#[pallet::storage]
-	#[pallet::unbounded]
-	pub(super) type Price<T: Config> =
-		StorageMap<_, Twox128, AssetIdOf<T>, (BalanceOf<T>, T::BlockNumber), OptionQuery>;
+	/// Quote currency of the prices the valuation API uses
+	const USD: &[u8] = b"USD";
 
-	// This is used to store real world values per token. NOTE: in AMMs the swap price is relative
-	// to the other token in the pair. Having a real world price for each token is useful for
-	// artbitrage opportunities.
-	#[pallet::storage]
-	#[pallet::unbounded]
-	pub(super) type PriceOracle<T: Config> =
-		StorageMap<_, Twox128, T::AccountId, bool, OptionQuery>;
+	/// LP tokens can be pooled again, e.g. the LLP token of a LP_PW / LP_FW pool. This bounds how
+	/// deep the valuation API decomposes them.
+	const MAX_VALUATION_DEPTH: u8 = 3;
 
 	// TODO: Change the pool ID from a u64 to a hash of the Pair. This can prevent duplicate pools,
 	// although currently the "root" sets the initial pools Use Blake hasher bc/ I plan to allow
@@ -137,6 +157,116 @@ pub mod pallet {
 		) -> Result<BalanceOf<T>, DispatchError> {
 			T::Assets::burn_from(asset_id, holder, amount)
 		}
+
+		/// USD value of `amount` of a token. Uses the token's fresh oracle price when it has one,
+		/// decomposes LP tokens into their pool's assets, and otherwise falls back to the price
+		/// implied by a pool pairing the token with an asset that has an oracle price.
+		pub fn usd_value(asset_id: AssetIdOf<T>, amount: BalanceOf<T>) -> Option<BalanceOf<T>> {
+			Self::value_of(asset_id, amount.saturated_into(), MAX_VALUATION_DEPTH)
+				.and_then(|value| BalanceOf::<T>::try_from(value).ok())
+		}
+
+		/// USD value of `amount` of a pool's LP token, i.e. of the share of both pool assets it
+		/// can be claimed for.
+		pub fn lp_usd_value(lp_id: AssetIdOf<T>, amount: BalanceOf<T>) -> Option<BalanceOf<T>> {
+			Self::lp_value(lp_id, amount.saturated_into(), MAX_VALUATION_DEPTH)
+				.and_then(|value| BalanceOf::<T>::try_from(value).ok())
+		}
+
+		/// Total USD value of the pool assets, LP tokens and priced tokens `who` holds. Tokens
+		/// that can't be valued count as zero.
+		pub fn portfolio_usd_value(who: &T::AccountId) -> BalanceOf<T> {
+			let mut assets: Vec<AssetIdOf<T>> = T::Prices::priced_assets();
+			for (asset_a, asset_b, lp, _) in Pools::<T>::iter_values() {
+				assets.extend([asset_a, asset_b, lp]);
+			}
+
+			let mut seen: Vec<AssetIdOf<T>> = Vec::with_capacity(assets.len());
+			let mut total = 0u128;
+			for asset_id in assets {
+				if seen.contains(&asset_id) {
+					continue
+				}
+				seen.push(asset_id);
+
+				let balance = T::Assets::balance(asset_id, who).saturated_into::<u128>();
+				if balance == 0 {
+					continue
+				}
+				let value = Self::value_of(asset_id, balance, MAX_VALUATION_DEPTH).unwrap_or(0);
+				total = total.saturating_add(value);
+			}
+
+			total.saturated_into()
+		}
+
+		fn value_of(asset_id: AssetIdOf<T>, amount: u128, depth: u8) -> Option<u128> {
+			if let Some(value) = Self::oracle_value(asset_id, amount) {
+				return Some(value)
+			}
+			if let Some(value) = Self::lp_value(asset_id, amount, depth) {
+				return Some(value)
+			}
+			Self::pool_implied_value(asset_id, amount)
+		}
+
+		fn oracle_value(asset_id: AssetIdOf<T>, amount: u128) -> Option<u128> {
+			let (price, quote, decimals) = T::Prices::price(&asset_id)?;
+			if quote.as_slice() != USD {
+				return None
+			}
+
+			valuation::to_quote_value(
+				amount,
+				T::Assets::decimals(&asset_id),
+				price.saturated_into(),
+				decimals,
+				T::UsdDecimals::get(),
+			)
+		}
+
+		fn lp_value(lp_id: AssetIdOf<T>, amount: u128, depth: u8) -> Option<u128> {
+			if depth == 0 {
+				return None
+			}
+			let (asset_a, asset_b, _, _) =
+				Pools::<T>::iter_values().find(|(_, _, lp, _)| *lp == lp_id)?;
+
+			let total_lp = T::Assets::total_issuance(lp_id).saturated_into::<u128>();
+			let amount_a = valuation::convert_by_ratio(
+				amount,
+				Self::pot(asset_a).saturated_into(),
+				total_lp,
+			)?;
+			let amount_b = valuation::convert_by_ratio(
+				amount,
+				Self::pot(asset_b).saturated_into(),
+				total_lp,
+			)?;
+
+			let value_a = Self::value_of(asset_a, amount_a, depth - 1)?;
+			let value_b = Self::value_of(asset_b, amount_b, depth - 1)?;
+			value_a.checked_add(value_b)
+		}
+
+		fn pool_implied_value(asset_id: AssetIdOf<T>, amount: u128) -> Option<u128> {
+			Pools::<T>::iter_values().find_map(|(asset_a, asset_b, _, _)| {
+				let other = if asset_a == asset_id {
+					asset_b
+				} else if asset_b == asset_id {
+					asset_a
+				} else {
+					return None
+				};
+
+				let other_amount = valuation::convert_by_ratio(
+					amount,
+					Self::pot(other).saturated_into(),
+					Self::pot(asset_id).saturated_into(),
+				)?;
+				Self::oracle_value(other, other_amount)
+			})
+		}
 	}
 
 	#[pallet::call]
@@ -288,18 +418,6 @@ pub mod pallet {
 			Ok(())
 		}
 
-		#[pallet::weight((1_000_000, Pays::Yes))]
-		pub fn authorize_pricing_oracle(
-			origin: OriginFor<T>,
-			who: T::AccountId,
-			is_permissioned: bool,
-		) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
-			PriceOracle::<T>::insert(&who, is_permissioned);
-			Self::deposit_event(Event::PriceOraclePermissionSet(who, is_permissioned));
-			Ok(Pays::No.into())
-		}
-
 		#[pallet::weight((1_000_000, Pays::Yes))]
 		pub fn create_pool(
 			origin: OriginFor<T>,
@@ -338,21 +456,5 @@ pub mod pallet {
 
 			Ok(Pays::No.into())
 		}
-
-		#[pallet::weight((1_000_000, Pays::Yes))]
-		pub fn set_price(
-			origin: OriginFor<T>,
-			asset_id: AssetIdOf<T>,
-			price: BalanceOf<T>,
-		) -> DispatchResultWithPostInfo {
-			let sender = ensure_signed(origin)?;
-			ensure!(PriceOracle::<T>::get(&sender).is_some(), Error::<T>::NotAuthorized);
-
-			let current_block = <frame_system::Pallet<T>>::block_number();
-			Price::<T>::insert(asset_id, (price, current_block));
-			Self::deposit_event(Event::PriceSet(asset_id, price, current_block));
-
-			Ok(Pays::No.into())
-		}
 	}
 }
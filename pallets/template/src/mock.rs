@@ -1,11 +1,17 @@
 use crate as pallet_template;
-use frame_support::traits::{ConstU16, ConstU64};
+use frame_support::{
+	parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64, ConstU8, GenesisBuild},
+	PalletId,
+};
 use frame_system as system;
+use pallet_custom_traits::PriceProvider;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
 };
+use std::{cell::RefCell, collections::BTreeMap};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -18,7 +24,9 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		TemplateModule: pallet_template::{Pallet, Call, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		Dex: pallet_template::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -40,20 +48,91 @@ impl system::Config for Test {
 	type BlockHashCount = ConstU64<250>;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 	type SS58Prefix = ConstU16<42>;
 	type OnSetCode = ();
-	type MaxConsumers = frame_support::traits::ConstU32<16>;
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = ConstU64<1>;
+	type AssetAccountDeposit = ConstU64<1>;
+	type MetadataDepositBase = ConstU64<0>;
+	type MetadataDepositPerByte = ConstU64<0>;
+	type ApprovalDeposit = ConstU64<1>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
+thread_local! {
+	static PRICES: RefCell<BTreeMap<u32, (u64, Vec<u8>, u8)>> = RefCell::new(BTreeMap::new());
+}
+
+/// Oracle prices set by the tests
+pub struct TestPrices;
+
+impl TestPrices {
+	pub fn set(asset_id: u32, price: u64, quote: &[u8], decimals: u8) {
+		PRICES
+			.with(|prices| prices.borrow_mut().insert(asset_id, (price, quote.to_vec(), decimals)));
+	}
+}
+
+impl PriceProvider<u32, u64> for TestPrices {
+	fn price(asset_id: &u32) -> Option<(u64, Vec<u8>, u8)> {
+		PRICES.with(|prices| prices.borrow().get(asset_id).cloned())
+	}
+
+	fn priced_assets() -> Vec<u32> {
+		PRICES.with(|prices| prices.borrow().keys().copied().collect())
+	}
+}
+
+parameter_types! {
+	pub const DexPot: PalletId = PalletId(*b"para/pot");
 }
 
 impl pallet_template::Config for Test {
 	type Event = Event;
+	type Assets = Assets;
+	type Prices = TestPrices;
+	type PalletId = DexPot;
+	type UsdDecimals = ConstU8<6>;
 }
 
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(ALICE, 1_000_000), (BOB, 1_000_000)] }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
 }
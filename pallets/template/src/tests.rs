@@ -1,20 +1,108 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{mock::*, Pools};
+use frame_support::{assert_ok, traits::Get};
+use sp_runtime::traits::AccountIdConversion;
+
+const PRICED: u32 = 1;
+const PAIRED: u32 = 2;
+const LP: u32 = 3;
+const POOL: u32 = 100;
+
+fn create_asset(asset_id: u32, decimals: u8) {
+	assert_ok!(Assets::force_create(Origin::root(), asset_id, ALICE, true, 1));
+	assert_ok!(Assets::force_set_metadata(
+		Origin::root(),
+		asset_id,
+		b"Token".to_vec(),
+		b"TKN".to_vec(),
+		decimals,
+		false
+	));
+}
+
+fn mint(asset_id: u32, who: u64, amount: u64) {
+	assert_ok!(Assets::mint(Origin::signed(ALICE), asset_id, who, amount));
+}
+
+/// A pool of 100 PRICED at $2 and 400 PAIRED, whose 50 LP tokens ALICE holds
+fn setup() {
+	create_asset(PRICED, 0);
+	create_asset(PAIRED, 0);
+	create_asset(LP, 0);
+	TestPrices::set(PRICED, 2_000_000, b"USD", 6);
+
+	let pot = DexPot::get().into_account_truncating();
+	mint(PRICED, pot, 100);
+	mint(PAIRED, pot, 400);
+	mint(LP, ALICE, 50);
+	Pools::<Test>::insert(POOL, (PRICED, PAIRED, LP, 40_000));
+}
+
+#[test]
+fn usd_value_uses_the_oracle_price() {
+	new_test_ext().execute_with(|| {
+		create_asset(PRICED, 10);
+		// $5 with 6 decimals
+		TestPrices::set(PRICED, 5_000_000, b"USD", 6);
+
+		assert_eq!(Dex::usd_value(PRICED, 20_000_000_000), Some(10_000_000));
+	});
+}
+
+#[test]
+fn usd_value_ignores_prices_in_other_quote_currencies() {
+	new_test_ext().execute_with(|| {
+		create_asset(PRICED, 0);
+		TestPrices::set(PRICED, 5_000_000, b"EUR", 6);
+
+		assert_eq!(Dex::usd_value(PRICED, 1), None);
+	});
+}
 
 #[test]
-fn it_works_for_default_value() {
+fn usd_value_falls_back_to_the_price_implied_by_a_pool() {
 	new_test_ext().execute_with(|| {
-		// Dispatch a signed extrinsic.
-		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
-		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
+		setup();
+
+		// 40 PAIRED trade for 10 PRICED, worth $20
+		assert_eq!(Dex::usd_value(PAIRED, 40), Some(20_000_000));
 	});
 }
 
 #[test]
-fn correct_error_for_none_value() {
+fn lp_usd_value_decomposes_the_pool_share() {
 	new_test_ext().execute_with(|| {
-		// Ensure the expected error is thrown when no value is present.
-		assert_noop!(TemplateModule::cause_error(Origin::signed(1)), Error::<Test>::NoneValue);
+		setup();
+
+		// Half the pool: 50 PRICED and 200 PAIRED, worth $100 each
+		assert_eq!(Dex::lp_usd_value(LP, 25), Some(200_000_000));
+		assert_eq!(Dex::usd_value(LP, 25), Some(200_000_000));
+		assert_eq!(Dex::lp_usd_value(PRICED, 25), None);
+	});
+}
+
+#[test]
+fn portfolio_usd_value_sums_pool_and_priced_assets() {
+	new_test_ext().execute_with(|| {
+		setup();
+		mint(PRICED, ALICE, 10);
+		mint(PAIRED, ALICE, 40);
+
+		// $20 + $20 + the $400 pool the LP tokens claim
+		assert_eq!(Dex::portfolio_usd_value(&ALICE), 440_000_000);
+		assert_eq!(Dex::portfolio_usd_value(&BOB), 0);
+	});
+}
+
+#[test]
+fn assets_with_unscalable_decimals_are_not_valued() {
+	new_test_ext().execute_with(|| {
+		setup();
+		let broken = 4;
+		create_asset(broken, 200);
+		TestPrices::set(broken, 1, b"USD", 0);
+		mint(broken, ALICE, 1_000);
+
+		assert_eq!(Dex::usd_value(broken, 1_000), None);
+		assert_eq!(Dex::portfolio_usd_value(&ALICE), 400_000_000);
 	});
 }
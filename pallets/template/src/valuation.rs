@@ -0,0 +1,75 @@
+use sp_core::U256;
+
+/// Value of `amount` of an asset with `asset_decimals` at `price` (a quote with `price_decimals`),
+/// expressed in the quote currency with `out_decimals`. `None` on overflow, e.g. for decimals
+/// too large to scale by, which asset metadata doesn't prevent.
+pub fn to_quote_value(
+	amount: u128,
+	asset_decimals: u8,
+	price: u128,
+	price_decimals: u8,
+	out_decimals: u8,
+) -> Option<u128> {
+	let numerator = U256::from(amount)
+		.checked_mul(U256::from(price))?
+		.checked_mul(checked_exp10(out_decimals)?)?;
+	let denominator = checked_exp10(asset_decimals)?.checked_mul(checked_exp10(price_decimals)?)?;
+	u128::try_from(numerator / denominator).ok()
+}
+
+/// `10^exp`, or `None` when it doesn't fit in a `U256`, i.e. from 78 on
+fn checked_exp10(exp: u8) -> Option<U256> {
+	U256::from(10u8).checked_pow(exp.into())
+}
+
+/// `amount * numerator / denominator`, e.g. to convert an amount of one pool asset into the
+/// other at the pool's ratio. `None` on overflow or a zero denominator.
+pub fn convert_by_ratio(amount: u128, numerator: u128, denominator: u128) -> Option<u128> {
+	if denominator == 0 {
+		return None
+	}
+	let value = U256::from(amount).checked_mul(U256::from(numerator))? / U256::from(denominator);
+	u128::try_from(value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_to_quote_value() {
+		// 2.5 tokens with 12 decimals at 1.20 USD (6 decimals)
+		let amount = 2_500_000_000_000;
+		let price = 1_200_000;
+
+		let value = to_quote_value(amount, 12, price, 6, 6);
+
+		assert_eq!(value, Some(3_000_000));
+	}
+
+	#[test]
+	fn test_to_quote_value_rescales_to_out_decimals() {
+		let amount = 1_000_000_000_000;
+		let price = 150_000_000; // 1.5 USD with 8 decimals
+
+		assert_eq!(to_quote_value(amount, 12, price, 8, 2), Some(150));
+		assert_eq!(to_quote_value(amount, 12, price, 8, 12), Some(1_500_000_000_000));
+	}
+
+	#[test]
+	fn test_to_quote_value_returns_none_on_overflow() {
+		assert_eq!(to_quote_value(u128::MAX, 0, u128::MAX, 0, 0), None);
+		assert_eq!(to_quote_value(1, 78, 1, 0, 0), None);
+		assert_eq!(to_quote_value(1, 0, 1, 255, 0), None);
+		assert_eq!(to_quote_value(1, 0, 1, 0, 255), None);
+	}
+
+	#[test]
+	fn test_convert_by_ratio() {
+		let total_a: u128 = 100_000_000_000_000_000;
+		let total_b: u128 = 200_000_000_000_000_000;
+
+		assert_eq!(convert_by_ratio(500, total_b, total_a), Some(1_000));
+		assert_eq!(convert_by_ratio(500, total_b, 0), None);
+	}
+}
@@ -15,9 +15,12 @@ use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, Verify},
+	traits::{
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, ConvertInto,
+		IdentifyAccount, NumberFor, Verify,
+	},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	ApplyExtrinsicResult, MultiSignature, SaturatedConversion,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -28,7 +31,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+		ConstU128, ConstU16, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness,
+		StorageInfo,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -44,8 +48,13 @@ use pallet_transaction_payment::CurrencyAdapter;
 pub use sp_runtime::BuildStorage;
 pub use sp_runtime::{Perbill, Permill};
 
+pub use pallet_nft_crafting;
+pub use pallet_nft_fractions;
+pub use pallet_nft_loot_boxes;
 pub use pallet_nft_maker;
+pub use pallet_oracle;
 pub use pallet_nft_marketplace;
+pub use pallet_oracle;
 pub use pallet_template;
 
 /// An index to a block.
@@ -61,6 +70,15 @@ pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::Account
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Identifier of a fungible asset.
+pub type AssetId = u64;
+
+/// Identifier of a NFT collection.
+pub type CollectionId = u32;
+
+/// Identifier of a NFT.
+pub type ItemId = u64;
+
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
@@ -103,7 +121,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 125,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -230,7 +248,7 @@ parameter_types! {
 impl pallet_assets::Config for Runtime {
 	type Event = Event;
 	type Balance = u128;
-	type AssetId = u64;
+	type AssetId = AssetId;
 	type Currency = Balances;
 	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
 	type AssetDeposit = ();
@@ -307,34 +325,177 @@ parameter_types! {
 impl pallet_template::Config for Runtime {
 	type Event = Event;
 	type Assets = Assets;
+	type Prices = Oracle;
 	type PalletId = DexPot;
+	type UsdDecimals = ConstU8<6>;
 }
 
 parameter_types! {
-	pub const TokenURILimit: u32 = 255u32;
-	pub const NFTMakerAccount: PalletId = PalletId(*b"nftCount");
+	pub const PriceFeedInterval: BlockNumber = 10;
+	pub const MaxPriceAge: BlockNumber = HOURS;
+	pub DefaultPriceDescriptor: pallet_oracle::types::PriceDescriptor<ConstU32<8>> =
+		pallet_oracle::types::PriceDescriptor {
+			quote: b"USD".to_vec().try_into().expect("Fits in the quote symbol limit"),
+			decimals: 6,
+		};
 }
 
-impl pallet_nft_maker::Config for Runtime {
+impl pallet_oracle::Config for Runtime {
 	type Event = Event;
-	type ItemId = u64;
-	type TokenURILimit = TokenURILimit;
-	type NFTMakerAccount = NFTMakerAccount;
+	type AssetId = AssetId;
+	type Balance = Balance;
+	type AuthorityId = pallet_oracle::crypto::OracleAuthId;
+	type PriceFeedInterval = PriceFeedInterval;
+	type PriceFeedAssetId = ConvertInto;
+	type MaxPriceHistory = ConstU32<24>;
+	type MaxPriceAge = MaxPriceAge;
+	type QuoteSymbolLimit = ConstU32<8>;
+	type MaxOracleScopes = ConstU32<16>;
+	type DefaultPriceDescriptor = DefaultPriceDescriptor;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	Call: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Index,
+	) -> Option<(Call, <UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload)> {
+		use codec::Encode;
+
+		let period =
+			BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2) as u64;
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+		);
+		let raw_payload = SignedPayload::new(call, extra).ok()?;
+		let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+		let (call, extra, _) = raw_payload.deconstruct();
+		Some((call, (sp_runtime::MultiAddress::Id(account), signature, extra)))
+	}
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	Call: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = Call;
 }
 
 parameter_types! {
-	pub const MarketplaceAccount: PalletId = PalletId(*b"saleIds!");
+	pub const TokenURILimit: u32 = 255u32;
+	pub const CollectionMetadataLimit: u32 = 255u32;
+	/// Collection the items minted before collections existed are moved into, owned by the
+	/// account that used to count their IDs
+	pub const LegacyCollectionId: CollectionId = 0;
+	pub LegacyCollection: (CollectionId, AccountId) =
+		(LegacyCollectionId::get(), PalletId(*b"nftCount").into_account_truncating());
+}
+
+impl pallet_nft_maker::Config for Runtime {
+	type Event = Event;
+	type ItemId = ItemId;
+	type CollectionId = CollectionId;
+	type TokenURILimit = TokenURILimit;
+	type CollectionMetadataLimit = CollectionMetadataLimit;
+	type AttributeKeyLimit = ConstU32<32>;
+	type AttributeValueLimit = ConstU32<64>;
+	type MaxAttributes = ConstU32<16>;
+	type MaxRoyalty = ConstU16<2_500>;
+	type MaxNestingDepth = ConstU32<3>;
+	type MaxChildren = ConstU32<8>;
+	type MaxBatchSize = ConstU32<250>;
+	type Currency = Balances;
+	type ItemDeposit = ConstU128<10_000>;
+	type DepositPerByte = ConstU128<100>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type Assets = Assets;
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
+	type OnBurn = NFTMarketplace;
+	type WeightInfo = pallet_nft_maker::weights::SubstrateWeight<Runtime>;
 }
 
 impl pallet_nft_marketplace::Config for Runtime {
 	type Event = Event;
 	type Assets = Assets;
-	type ItemId = u64;
+	type CollectionId = CollectionId;
+	type ItemId = ItemId;
 	type SaleId = u64;
-	type MarketplaceAccount = MarketplaceAccount;
 	type NFT = NFTMaker;
 }
 
+parameter_types! {
+	pub const FractionsCustody: PalletId = PalletId(*b"nftfract");
+	pub const FirstShareAssetId: AssetId = 1 << 32;
+	pub const ShareName: &'static str = "Fraction";
+	pub const ShareSymbol: &'static str = "FRAC";
+}
+
+impl pallet_nft_fractions::Config for Runtime {
+	type Event = Event;
+	type AssetId = AssetId;
+	type Assets = Assets;
+	type CollectionId = CollectionId;
+	type ItemId = ItemId;
+	type Nfts = NFTMaker;
+	type PalletId = FractionsCustody;
+	type FirstShareAssetId = FirstShareAssetId;
+	type ShareName = ShareName;
+	type ShareSymbol = ShareSymbol;
+	type Currency = Balances;
+	type VaultDeposit = ConstU128<100_000>;
+}
+
+impl pallet_nft_crafting::Config for Runtime {
+	type Event = Event;
+	type Assets = Assets;
+	type CollectionId = CollectionId;
+	type ItemId = ItemId;
+	type RecipeId = u32;
+	type Nfts = NFTMaker;
+	type AttributeKeyLimit = ConstU32<32>;
+	type AttributeValueLimit = ConstU32<64>;
+	type MaxInputs = ConstU32<8>;
+	type MaxOutputs = ConstU32<4>;
+	type MaxOutputAttributes = ConstU32<8>;
+}
+
+impl pallet_nft_loot_boxes::Config for Runtime {
+	type Event = Event;
+	type CollectionId = CollectionId;
+	type ItemId = ItemId;
+	type OpeningId = u64;
+	type Nfts = NFTMaker;
+	// Collators can bias the collective flip, but loot is drawn with it and a secret openers
+	// commit to, so neither side alone decides the loot. Switch to BABE's VRF randomness if the
+	// chain moves off Aura.
+	type Randomness = RandomnessCollectiveFlip;
+	type RevealDelay = ConstU32<3>;
+	type ClaimPeriod = ConstU32<DAYS>;
+	type AttributeKeyLimit = ConstU32<32>;
+	type AttributeValueLimit = ConstU32<64>;
+	type MaxDrops = ConstU32<32>;
+	type MaxDropAttributes = ConstU32<8>;
+}
+
 construct_runtime!(
 	pub enum Runtime where
 		Block = Block,
@@ -350,10 +511,14 @@ construct_runtime!(
 		TransactionPayment: pallet_transaction_payment,
 		Sudo: pallet_sudo,
 		Dex: pallet_template,
+		Oracle: pallet_oracle,
 		Nicks: pallet_nicks,
 		Assets: pallet_assets,
 		NFTMaker: pallet_nft_maker,
 		NFTMarketplace: pallet_nft_marketplace,
+		NFTFractions: pallet_nft_fractions,
+		NFTCrafting: pallet_nft_crafting,
+		NFTLootBoxes: pallet_nft_loot_boxes,
 	}
 );
 
@@ -374,6 +539,12 @@ pub type SignedExtra = (
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
+/// Storage migrations run on the next runtime upgrade.
+pub type Migrations = (
+	pallet_oracle::migrations::MigrateFromDex<Runtime, Dex>,
+	pallet_nft_maker::migrations::MigrateToV1<Runtime, LegacyCollection>,
+	pallet_nft_marketplace::migrations::MigrateToV1<Runtime, LegacyCollectionId>,
+);
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -381,6 +552,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -395,6 +567,7 @@ mod benches {
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
 		[pallet_template, TemplateModule]
+		[pallet_nft_maker, NFTMaker]
 	);
 }
 
@@ -529,6 +702,34 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_template_rpc_runtime_api::ValuationApi<Block, AccountId, AssetId, Balance> for Runtime {
+		fn usd_value(asset_id: AssetId, amount: Balance) -> Option<Balance> {
+			Dex::usd_value(asset_id, amount)
+		}
+
+		fn lp_usd_value(lp_id: AssetId, amount: Balance) -> Option<Balance> {
+			Dex::lp_usd_value(lp_id, amount)
+		}
+
+		fn portfolio_usd_value(who: AccountId) -> Balance {
+			Dex::portfolio_usd_value(&who)
+		}
+	}
+
+	impl pallet_nft_maker_rpc_runtime_api::NftApi<Block, AccountId, CollectionId, ItemId> for Runtime {
+		fn items_of(who: AccountId, start: u32, limit: u32) -> Vec<(CollectionId, ItemId)> {
+			NFTMaker::items_of(&who, start, limit)
+		}
+
+		fn token_uri(collection_id: CollectionId, item_id: ItemId) -> Option<Vec<u8>> {
+			NFTMaker::token_uri(collection_id, item_id)
+		}
+
+		fn token_content_hash(collection_id: CollectionId, item_id: ItemId) -> Option<[u8; 32]> {
+			NFTMaker::token_content_hash(collection_id, item_id)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
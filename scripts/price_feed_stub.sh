@@ -0,0 +1,15 @@
+#!/usr/bin/env bash
+# This script is meant to be run on Unix/Linux based systems
+set -e
+
+PORT=${PORT:-8000}
+FEED_DIR=$(mktemp -d)
+
+# Prices are keyed by asset ID and given in USD with 6 decimals, the default price descriptor,
+# so these are $1, $2 and $0.50
+echo '{"1": 1000000, "2": 2000000, "4": 500000}' > $FEED_DIR/prices
+
+echo "*** Serving price feed on http://localhost:$PORT/prices"
+
+cd $FEED_DIR
+python3 -m http.server $PORT